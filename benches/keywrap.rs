@@ -0,0 +1,123 @@
+use aes_keywrap_rs::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn bench_128bit_key_wrap(c: &mut Criterion) {
+    c.bench_function("128bit_key_wrap", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+                let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+                aes_wrap_key(&kek, &plain).unwrap();
+            }
+        })
+    });
+}
+
+fn bench_128bit_key_unwrap(c: &mut Criterion) {
+    c.bench_function("128bit_key_unwrap", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+                let cipher =
+                    hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+                aes_unwrap_key(&kek, &cipher).unwrap();
+            }
+        })
+    });
+}
+
+fn bench_256bit_key_wrap(c: &mut Criterion) {
+    c.bench_function("256bit_key_wrap", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                let kek =
+                    hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+                        .unwrap();
+                let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+                aes_wrap_key(&kek, &plain).unwrap();
+            }
+        })
+    });
+}
+
+fn bench_256bit_key_unwrap(c: &mut Criterion) {
+    c.bench_function("256bit_key_unwrap", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                let kek =
+                    hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+                        .unwrap();
+                let cipher =
+                    hex::decode("64E8C3F9CE0F5BA263E9777905818A2A93C8191E7D6E8AE7").unwrap();
+                aes_unwrap_key(&kek, &cipher).unwrap();
+            }
+        })
+    });
+}
+
+// general (n > 2) semiblock loop, where the byte-array conversions sit
+fn bench_256bit_kek_and_256bit_key_unwrap(c: &mut Criterion) {
+    let kek =
+        hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F").unwrap();
+    let cipher = hex::decode(
+        "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21",
+    )
+    .unwrap();
+    c.bench_function("256bit_kek_and_256bit_key_unwrap", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                aes_unwrap_key(&kek, &cipher).unwrap();
+            }
+        })
+    });
+}
+
+fn bench_256bit_kek_and_256bit_key_unwrap_context(c: &mut Criterion) {
+    let kek =
+        hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F").unwrap();
+    let cipher = hex::decode(
+        "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21",
+    )
+    .unwrap();
+    let kw = Aes256Kw::new(&kek).unwrap();
+    c.bench_function("256bit_kek_and_256bit_key_unwrap_context", |b| {
+        b.iter(|| {
+            for _ in 0..100 {
+                kw.unwrap(&cipher).unwrap();
+            }
+        })
+    });
+}
+
+fn bench_64_keys_wrap_per_key(c: &mut Criterion) {
+    let kek = [0x5au8; 32];
+    let keys = vec![[0x11u8; 32]; 64];
+    c.bench_function("64_keys_wrap_per_key", |b| {
+        b.iter(|| {
+            for key in &keys {
+                try_wrap_key(&kek, key).unwrap();
+            }
+        })
+    });
+}
+
+fn bench_64_keys_wrap_multilane(c: &mut Criterion) {
+    let kek = [0x5au8; 32];
+    let keys = vec![[0x11u8; 32]; 64];
+    c.bench_function("64_keys_wrap_multilane", |b| {
+        b.iter(|| wrap_keys_multilane(&kek, Algorithm::Kw, &keys))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_128bit_key_wrap,
+    bench_128bit_key_unwrap,
+    bench_256bit_key_wrap,
+    bench_256bit_key_unwrap,
+    bench_256bit_kek_and_256bit_key_unwrap,
+    bench_256bit_kek_and_256bit_key_unwrap_context,
+    bench_64_keys_wrap_per_key,
+    bench_64_keys_wrap_multilane
+);
+criterion_main!(benches);
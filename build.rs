@@ -0,0 +1,30 @@
+use std::env;
+
+fn main() {
+    // Node resolves the N-API symbols when it loads the addon
+    #[cfg(feature = "node")]
+    napi_build::setup();
+
+    // AES block function, first enabled one wins (see src/backend.rs)
+    let enabled = |dep: &str| env::var_os(format!("CARGO_FEATURE_{}", dep)).is_some();
+    let windows = env::var_os("CARGO_CFG_WINDOWS").is_some();
+    let apple = env::var("CARGO_CFG_TARGET_VENDOR").is_ok_and(|v| v == "apple");
+    let backend = if enabled("OPENSSL") {
+        "openssl"
+    } else if enabled("WIN_CRYPTO_NG") && windows {
+        "cng"
+    } else if enabled("COMMON_CRYPTO") && apple {
+        "commoncrypto"
+    } else if enabled("AES") {
+        "aes"
+    } else if enabled("SELF_CONTAINED") {
+        "self-contained"
+    } else {
+        "crypto2"
+    };
+    println!(
+        "cargo:rustc-check-cfg=cfg(kw_backend, values(\"openssl\", \"cng\", \"commoncrypto\", \
+         \"aes\", \"self-contained\", \"crypto2\"))"
+    );
+    println!("cargo:rustc-cfg=kw_backend=\"{}\"", backend);
+}
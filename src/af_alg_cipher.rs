@@ -0,0 +1,124 @@
+use crate::BlockCipher128;
+use core::fmt;
+use nix::errno::Errno;
+use nix::libc;
+use nix::sys::socket::{
+    accept, bind, sendmsg, setsockopt, socket, sockopt::AlgSetKey, AddressFamily, AlgAddr,
+    ControlMessage, MsgFlags, SockFlag, SockType,
+};
+use nix::unistd::{close, read};
+use std::io::IoSlice;
+use std::os::fd::{AsRawFd, OwnedFd, RawFd};
+
+// Linux kernel crypto API block cipher (`af-alg` feature)
+//
+// For SoCs whose crypto engine (CAAM, CESA, sun8i-ce, ...) is only reachable
+// through the kernel: the KEK is set on an AF_ALG "skcipher" socket once and
+// every block of W / W^-1 is one sendmsg/read on the request socket, while
+// `BlockCipherKw` does the RFC3394/RFC5649 chaining. The kernel picks the
+// highest-priority "ecb(aes)" implementation unless a driver name is given.
+// Two syscalls per block, so like `Pkcs11Cipher` it suits KEK-sized
+// payloads, not bulk data.
+
+/// AES-ECB transform from the kernel crypto API, as a `BlockCipher128`
+///
+/// ```ignore
+/// let mut kw = BlockCipherKw::new(AfAlgCipher::new(&kek)?);
+/// let wrapped = kw.wrap_with_pad(&dek)?;
+/// ```
+pub struct AfAlgCipher {
+    // request socket from accept(), closed on drop
+    op: RawFd,
+    // bound transform socket holding the key
+    _tfm: OwnedFd,
+}
+
+impl fmt::Debug for AfAlgCipher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AfAlgCipher(..redacted..)")
+    }
+}
+
+impl AfAlgCipher {
+    /// Key the kernel's preferred `ecb(aes)` with a 16, 24 or 32 byte `kek`
+    pub fn new(kek: &[u8]) -> Result<Self, Errno> {
+        Self::with_algorithm("ecb(aes)", kek)
+    }
+
+    /// Key a specific kernel algorithm or driver name, e.g. `"ecb-aes-caam"`
+    /// (see /proc/crypto); it must be a 16-byte block ECB skcipher
+    pub fn with_algorithm(name: &str, kek: &[u8]) -> Result<Self, Errno> {
+        let tfm = socket(
+            AddressFamily::Alg,
+            SockType::SeqPacket,
+            SockFlag::SOCK_CLOEXEC,
+            None,
+        )?;
+        bind(tfm.as_raw_fd(), &AlgAddr::new("skcipher", name))?;
+        setsockopt(&tfm, AlgSetKey::default(), &kek)?;
+        let op = accept(tfm.as_raw_fd())?;
+        Ok(AfAlgCipher { op, _tfm: tfm })
+    }
+
+    fn crypt(&mut self, op: libc::c_int, block: &mut [u8; 16]) -> Result<(), Errno> {
+        let cmsgs = [ControlMessage::AlgSetOp(&op)];
+        sendmsg::<AlgAddr>(
+            self.op,
+            &[IoSlice::new(&block[..])],
+            &cmsgs,
+            MsgFlags::empty(),
+            None,
+        )?;
+        match read(self.op, block)? {
+            16 => Ok(()),
+            _ => Err(Errno::EIO),
+        }
+    }
+}
+
+impl Drop for AfAlgCipher {
+    fn drop(&mut self) {
+        let _ = close(self.op);
+    }
+}
+
+impl BlockCipher128 for AfAlgCipher {
+    type Error = Errno;
+
+    fn encrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Errno> {
+        self.crypt(libc::ALG_OP_ENCRYPT, block)
+    }
+
+    fn decrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Errno> {
+        self.crypt(libc::ALG_OP_DECRYPT, block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlockCipherKw;
+
+    #[test]
+    fn test_rfc3394_vector() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+
+        // kernels without CONFIG_CRYPTO_USER_API_SKCIPHER (or sandboxes
+        // blocking AF_ALG) can't run this
+        let alg = match AfAlgCipher::new(&kek) {
+            Ok(alg) => alg,
+            Err(e @ (Errno::EAFNOSUPPORT | Errno::EPERM | Errno::EACCES)) => {
+                eprintln!("skipping test_rfc3394_vector: AF_ALG unavailable ({})", e);
+                return;
+            }
+            Err(e) => panic!("{}", e),
+        };
+        let mut kw = BlockCipherKw::new(alg);
+        assert_eq!(cipher, kw.wrap(&plain).unwrap());
+        assert_eq!(&plain[..], kw.unwrap(&cipher).unwrap().expose_secret());
+
+        assert_eq!(Err(Errno::EINVAL), AfAlgCipher::new(&kek[..15]).map(drop));
+    }
+}
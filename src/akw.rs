@@ -0,0 +1,63 @@
+use crate::cms_tdes;
+use crate::{KeyWrapError, UnwrappedKey};
+use alloc::vec::Vec;
+use rand_core::{CryptoRng, RngCore};
+
+// ANSI X9.102 AKW1 (`akw` feature)
+//
+// AKW1 is the RFC 3217 CMS Triple-DES key wrap applied to any key that is a
+// whole number of 8-octet blocks: SHA-1 ICV, CBC under a random IV, byte
+// reversal and a second CBC pass under a fixed IV. X9.102's AESKW and TDKW
+// are `wrap_key` and `tdes_wrap_key` (`tkw` feature). AKW2 is not provided.
+
+/// Wrap key with a 24-byte three-key TDEA KEK (X9.102 AKW1); the IV is
+/// drawn from `rng`
+pub fn akw1_wrap_key<R: CryptoRng + RngCore>(
+    kek: &[u8],
+    plaintext: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, KeyWrapError> {
+    if plaintext.is_empty() || !plaintext.len().is_multiple_of(8) {
+        return Err(KeyWrapError::InvalidPlaintextLength(plaintext.len()));
+    }
+    cms_tdes::wrap(kek, plaintext, rng)
+}
+
+/// Unwrap key and Check ICV (X9.102 AKW1)
+pub fn akw1_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    let buf = cms_tdes::unwrap(kek, wrapped)?;
+    UnwrappedKey::from_slice(&buf[8..buf.len() - 8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::CountingRng;
+
+    #[test]
+    fn test_akw1() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F1011121314151617").unwrap();
+        let key = hex::decode("00112233445566778899AABBCCDDEEFF0001020304050607").unwrap();
+        let mut wrapped = akw1_wrap_key(&kek, &key, &mut CountingRng(0)).unwrap();
+        assert_eq!(
+            hex::decode(
+                "3B7506FAA3547D4534B13C3444124E579C95231D1738439EBD0E81E3C45DDAB8C5AC1A86A4853294"
+            )
+            .unwrap(),
+            wrapped
+        );
+        assert_eq!(
+            &key[..],
+            akw1_unwrap_key(&kek, &wrapped).unwrap().expose_secret()
+        );
+        wrapped[20] ^= 1;
+        assert_eq!(
+            KeyWrapError::IntegrityCheckFailed,
+            akw1_unwrap_key(&kek, &wrapped).unwrap_err()
+        );
+        assert_eq!(
+            KeyWrapError::InvalidPlaintextLength(20),
+            akw1_wrap_key(&kek, &key[..20], &mut CountingRng(0)).unwrap_err()
+        );
+    }
+}
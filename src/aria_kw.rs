@@ -0,0 +1,155 @@
+use crate::{Kek, KeyWrapError, UnwrappedKey};
+use alloc::string::String;
+use alloc::vec::Vec;
+use aria::{Aria128, Aria192, Aria256};
+
+// ARIA key wrap (RFC 5794 cipher, `aria-kw` feature)
+//
+// KW and KWP from SP 800-38F instantiated with ARIA instead of AES, as used
+// by KCMVP validated modules. RFC 5794 only specifies the block cipher, so
+// there are no published wrap vectors; the tests below were produced with
+// OpenSSL's ARIA-ECB driving the RFC3394/RFC5649 algorithms.
+
+macro_rules! with_aria_kek {
+    ($kek:expr, $op:ident, $input:expr) => {
+        match $kek.len() {
+            16 => Kek::<Aria128>::new_from_slice($kek)?.$op($input),
+            24 => Kek::<Aria192>::new_from_slice($kek)?.$op($input),
+            32 => Kek::<Aria256>::new_from_slice($kek)?.$op($input),
+            len => Err(KeyWrapError::InvalidKekLength(len)),
+        }
+    };
+}
+
+/// Wrap key with ARIA and the IV defined in RFC3394
+pub fn try_aria_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    with_aria_kek!(kek, wrap, plaintext)
+}
+
+/// Unwrap key with ARIA and Check IV
+pub fn try_aria_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    with_aria_kek!(kek, unwrap, wrapped)
+}
+
+/// Wrap key with ARIA and the AIV defined in RFC5649
+pub fn try_aria_wrap_key_with_pad(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    with_aria_kek!(kek, wrap_with_pad, plaintext)
+}
+
+/// Unwrap key with ARIA and Check AIV and padding
+pub fn try_aria_unwrap_key_with_pad(
+    kek: &[u8],
+    wrapped: &[u8],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    with_aria_kek!(kek, unwrap_with_pad, wrapped)
+}
+
+/// Wrap key with ARIA and the IV defined in RFC3394
+pub fn aria_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_aria_wrap_key(kek, plaintext)?)
+}
+
+/// Unwrap key with ARIA and Check IV
+pub fn aria_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_aria_unwrap_key(kek, wrapped)?.expose_secret().to_vec())
+}
+
+/// Wrap key with ARIA and the AIV defined in RFC5649
+pub fn aria_wrap_key_with_pad(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_aria_wrap_key_with_pad(kek, plaintext)?)
+}
+
+/// Unwrap key with ARIA and Check AIV and padding
+pub fn aria_unwrap_key_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_aria_unwrap_key_with_pad(kek, wrapped)?
+        .expose_secret()
+        .to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aria_vectors() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F")
+            .unwrap();
+        for (pad, kek_len, plain_len, cipher) in [
+            (
+                false,
+                16,
+                16,
+                "A93F148D4909D85F1AAE656909879275AE597B3ACF9D60DB",
+            ),
+            (
+                false,
+                24,
+                16,
+                "62C0CC597CEA0A97C1DDFD9384BA51A9F4EC7AAC30F7CEDC",
+            ),
+            (
+                false,
+                32,
+                16,
+                "1F68AC246E2519B0235E1474867B08F606BCF85BEF006EBA",
+            ),
+            (
+                false,
+                24,
+                24,
+                "D3DE092CDAE2C71E85AA964924DCE3C96736BD22CAD51F75823102BBB305D230",
+            ),
+            (
+                false,
+                32,
+                24,
+                "32E13D029906B74EAD0BD0CF2F4F73DFAD439B0D27AB591E6CFEFDD7DB5A2A98",
+            ),
+            (
+                false,
+                32,
+                32,
+                "9E98AAD469E60840C258F8396AA05F826F7353BDD1257F909C5576967B0F8C7BCD9FE4157BF4D844",
+            ),
+            (
+                true,
+                16,
+                20,
+                "3F88940C365B2985BF88732A7AAA71F2979667C36FE82231434945E1C1AF38E8",
+            ),
+            (
+                true,
+                24,
+                20,
+                "6EA65A7657031BDFE87F313C2464B0DF5DAED5CDF7CC147CAB6EAD456D9416E6",
+            ),
+            (
+                true,
+                32,
+                20,
+                "614BC20FB7B0182E7421CD6CE5C454696A8E2DDE2B1EE5694C7E1D1D6D38475E",
+            ),
+            (true, 16, 7, "0715DD19E4266AB31EFF29ACBAEE1200"),
+            (true, 32, 7, "CD52AB0EED40D39717E5A681D77150F3"),
+        ]
+        .iter()
+        {
+            let kek = &kek[..*kek_len];
+            let plain = &plain[..*plain_len];
+            let cipher = hex::decode(cipher).unwrap();
+            if *pad {
+                assert_eq!(cipher, aria_wrap_key_with_pad(kek, plain).unwrap());
+                assert_eq!(plain, &aria_unwrap_key_with_pad(kek, &cipher).unwrap()[..]);
+            } else {
+                assert_eq!(cipher, aria_wrap_key(kek, plain).unwrap());
+                assert_eq!(plain, &aria_unwrap_key(kek, &cipher).unwrap()[..]);
+            }
+        }
+        assert_eq!(
+            KeyWrapError::InvalidKekLength(20),
+            try_aria_wrap_key(&kek[..20], &plain).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+// AES block function selection
+//
+// The key wrap code only needs `AesXxxEcb::new/encrypt/decrypt`, reached
+// through `AesEcb` so that backends calling into a provider (OpenSSL, CNG,
+// CommonCrypto), which may refuse to run, report `BlockCipherFailed` instead of panicking.
+// Which implementation provides it is chosen by feature; build.rs sets
+// `kw_backend` to the first one enabled, in this order:
+// * `backend-openssl` (`openssl`): OpenSSL's EVP AES-ECB, e.g. a FIPS provider
+// * `backend-cng` (`win-crypto-ng`): Windows CNG (BCrypt), Windows targets only
+// * `backend-commoncrypto` (`common-crypto`): Apple CommonCrypto, macOS / iOS
+//   targets only
+// * `backend-aes` (`aes`, default): RustCrypto `aes`, with runtime
+//   AES-NI / ARMv8-CE detection
+// * `self-contained`: the vendored constant-time AES (`soft_aes`)
+// * `backend-crypto2` (`crypto2`): crypto2, AES-NI / ARMv8 only if compiled
+//   in; needs nightly
+
+#[cfg(kw_backend = "cng")]
+pub(crate) use crate::cng_aes::{Aes128Ecb, Aes192Ecb, Aes256Ecb};
+#[cfg(kw_backend = "commoncrypto")]
+pub(crate) use crate::commoncrypto_aes::{Aes128Ecb, Aes192Ecb, Aes256Ecb};
+#[cfg(kw_backend = "openssl")]
+pub(crate) use crate::openssl_aes::{Aes128Ecb, Aes192Ecb, Aes256Ecb};
+#[cfg(kw_backend = "aes")]
+pub(crate) use crate::rustcrypto_aes::{Aes128Ecb, Aes192Ecb, Aes256Ecb};
+#[cfg(kw_backend = "self-contained")]
+pub(crate) use crate::soft_aes::{Aes128Ecb, Aes192Ecb, Aes256Ecb};
+#[cfg(kw_backend = "crypto2")]
+pub(crate) use crypto2::blockmode::{Aes128Ecb, Aes192Ecb, Aes256Ecb};
+
+use crate::KeyWrapError;
+
+/// AES-ECB under one key, as the key wrap code drives it
+pub(crate) trait AesEcb: Sized {
+    fn try_new(key: &[u8]) -> Result<Self, KeyWrapError>;
+
+    fn try_clone(&self) -> Result<Self, KeyWrapError>;
+
+    /// Encrypt whole blocks in place
+    fn try_encrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError>;
+
+    /// Decrypt whole blocks in place
+    fn try_decrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError>;
+}
+
+// CommonCrypto's `new` can't fail but its `encrypt/decrypt` can
+#[cfg(kw_backend = "commoncrypto")]
+macro_rules! ecb_result {
+    ($e:expr) => {
+        $e
+    };
+}
+
+// the software backends can't fail at all
+#[cfg(not(any(
+    kw_backend = "openssl",
+    kw_backend = "cng",
+    kw_backend = "commoncrypto"
+)))]
+macro_rules! ecb_result {
+    ($e:expr) => {{
+        $e;
+        Ok(())
+    }};
+}
+
+// backends whose `new` can't fail
+#[cfg(not(any(kw_backend = "openssl", kw_backend = "cng")))]
+macro_rules! impl_aes_ecb {
+    ($cipher:ty) => {
+        impl AesEcb for $cipher {
+            fn try_new(key: &[u8]) -> Result<Self, KeyWrapError> {
+                Ok(<$cipher>::new(key))
+            }
+
+            fn try_clone(&self) -> Result<Self, KeyWrapError> {
+                Ok(self.clone())
+            }
+
+            fn try_encrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                ecb_result!(self.encrypt(blocks))
+            }
+
+            fn try_decrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                ecb_result!(self.decrypt(blocks))
+            }
+        }
+    };
+}
+
+// backends whose `new/encrypt/decrypt` already return `KeyWrapError`
+#[cfg(any(kw_backend = "openssl", kw_backend = "cng"))]
+macro_rules! impl_aes_ecb {
+    ($cipher:ty) => {
+        impl AesEcb for $cipher {
+            fn try_new(key: &[u8]) -> Result<Self, KeyWrapError> {
+                <$cipher>::new(key)
+            }
+
+            fn try_clone(&self) -> Result<Self, KeyWrapError> {
+                <$cipher>::try_clone(self)
+            }
+
+            fn try_encrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                self.encrypt(blocks)
+            }
+
+            fn try_decrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                self.decrypt(blocks)
+            }
+        }
+    };
+}
+
+impl_aes_ecb!(Aes128Ecb);
+impl_aes_ecb!(Aes192Ecb);
+impl_aes_ecb!(Aes256Ecb);
+
+/// Name of the AES implementation in use, for diagnostics
+///
+/// `"openssl"` with `backend-openssl`, `"cng"` with `backend-cng`,
+/// `"commoncrypto"` with `backend-commoncrypto`. With the `aes` feature this
+/// reflects what the CPU was detected to support at runtime: `"aes-ni"`, `"armv8-ce"` or `"fixsliced"`
+/// (`"aes"` without `std`, where detection isn't available).
+/// Otherwise it is fixed at build time: `"crypto2-aes-ni"`,
+/// `"crypto2-armv8"`, `"crypto2-generic"` or `"self-contained"`.
+pub fn aes_backend() -> &'static str {
+    #[cfg(kw_backend = "openssl")]
+    {
+        "openssl"
+    }
+
+    #[cfg(kw_backend = "cng")]
+    {
+        "cng"
+    }
+
+    #[cfg(kw_backend = "commoncrypto")]
+    {
+        "commoncrypto"
+    }
+
+    #[cfg(all(kw_backend = "aes", feature = "std"))]
+    {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if std::is_x86_feature_detected!("aes") && std::is_x86_feature_detected!("sse2") {
+                return "aes-ni";
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("aes") {
+                return "armv8-ce";
+            }
+        }
+        "fixsliced"
+    }
+
+    #[cfg(all(kw_backend = "aes", not(feature = "std")))]
+    {
+        "aes"
+    }
+
+    #[cfg(kw_backend = "self-contained")]
+    {
+        "self-contained"
+    }
+
+    #[cfg(kw_backend = "crypto2")]
+    {
+        if cfg!(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "aes",
+            target_feature = "sse2"
+        )) {
+            "crypto2-aes-ni"
+        } else if cfg!(all(target_arch = "aarch64", target_feature = "aes")) {
+            "crypto2-armv8"
+        } else {
+            "crypto2-generic"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{try_unwrap_key, try_wrap_key};
+
+    #[test]
+    fn test_backend() {
+        let backend = aes_backend();
+        if cfg!(kw_backend = "openssl") {
+            assert_eq!("openssl", backend);
+        } else if cfg!(kw_backend = "aes") {
+            assert!(["aes-ni", "armv8-ce", "fixsliced"].contains(&backend));
+        }
+
+        // RFC3394 4.6 through whichever backend is selected
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F")
+            .unwrap();
+        let cipher = hex::decode(
+            "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21",
+        )
+        .unwrap();
+        assert_eq!(cipher, try_wrap_key(&kek, &plain).unwrap());
+        assert_eq!(
+            &plain[..],
+            try_unwrap_key(&kek, &cipher).unwrap().expose_secret()
+        );
+    }
+}
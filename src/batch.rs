@@ -0,0 +1,93 @@
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, KeyWrapError,
+    UnwrappedKey,
+};
+use rayon::prelude::*;
+
+// Parallel batch API (`rayon` feature)
+//
+// Each item is wrapped or unwrapped independently on the rayon thread pool
+// and reported separately, in input order, so one corrupted blob doesn't
+// stop the others.
+
+/// Wrap (RFC3394) each key under `kek` in parallel
+pub fn wrap_keys_batch<T: AsRef<[u8]> + Sync>(
+    kek: &[u8],
+    plaintexts: &[T],
+) -> Vec<Result<Vec<u8>, KeyWrapError>> {
+    plaintexts
+        .par_iter()
+        .map(|p| try_wrap_key(kek, p.as_ref()))
+        .collect()
+}
+
+/// Unwrap (RFC3394) each key under `kek` in parallel
+pub fn unwrap_keys_batch<T: AsRef<[u8]> + Sync>(
+    kek: &[u8],
+    wrapped: &[T],
+) -> Vec<Result<UnwrappedKey, KeyWrapError>> {
+    wrapped
+        .par_iter()
+        .map(|w| try_unwrap_key(kek, w.as_ref()))
+        .collect()
+}
+
+/// Wrap (RFC5649) each key under `kek` in parallel
+pub fn wrap_keys_with_pad_batch<T: AsRef<[u8]> + Sync>(
+    kek: &[u8],
+    plaintexts: &[T],
+) -> Vec<Result<Vec<u8>, KeyWrapError>> {
+    plaintexts
+        .par_iter()
+        .map(|p| try_wrap_key_with_pad(kek, p.as_ref()))
+        .collect()
+}
+
+/// Unwrap (RFC5649) each key under `kek` in parallel
+pub fn unwrap_keys_with_pad_batch<T: AsRef<[u8]> + Sync>(
+    kek: &[u8],
+    wrapped: &[T],
+) -> Vec<Result<UnwrappedKey, KeyWrapError>> {
+    wrapped
+        .par_iter()
+        .map(|w| try_unwrap_key_with_pad(kek, w.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_matches_sequential() {
+        let kek = [0x5au8; 32];
+        let keys: Vec<Vec<u8>> = (1..40u8).map(|n| vec![n; n as usize]).collect();
+        let wrapped: Vec<Vec<u8>> = wrap_keys_with_pad_batch(&kek, &keys)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        for (key, w) in keys.iter().zip(&wrapped) {
+            assert_eq!(&try_wrap_key_with_pad(&kek, key).unwrap(), w);
+        }
+        let unwrapped = unwrap_keys_with_pad_batch(&kek, &wrapped);
+        for (key, u) in keys.iter().zip(unwrapped) {
+            assert_eq!(&key[..], u.unwrap().expose_secret());
+        }
+
+        let plain = [[0x11u8; 16], [0x22u8; 16]];
+        let mut wrapped: Vec<Vec<u8>> = wrap_keys_batch(&kek, &plain)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        wrapped[0][3] ^= 1;
+        let unwrapped = unwrap_keys_batch(&kek, &wrapped);
+        assert_eq!(
+            Some(KeyWrapError::IntegrityCheckFailed),
+            unwrapped[0].as_ref().err().copied()
+        );
+        assert_eq!(
+            &plain[1][..],
+            unwrapped[1].as_ref().unwrap().expose_secret()
+        );
+    }
+}
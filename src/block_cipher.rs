@@ -0,0 +1,404 @@
+use crate::backend::{Aes128Ecb, Aes192Ecb, Aes256Ecb, AesEcb};
+use crate::bytes::to_u8_8_array;
+use crate::{
+    kw_unwrap_in_place_with, kw_wrap_in_place_with, kw_wrap_with, kwp_unwrap_in_place_with,
+    kwp_wrap_in_place_with, kwp_wrap_with, self_test, unwrap_kw_checked, unwrap_kwp_checked,
+    KeyWrapError, UnwrappedKey,
+};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+use zeroize::Zeroize;
+
+// Pluggable block cipher
+//
+// W and W^-1 only ever encrypt or decrypt one 16-byte block at a time under
+// the KEK, so that is all they ask of the cipher. The built-in AES backends
+// implement `BlockCipher128`; `BlockCipherKw` runs the RFC3394/RFC5649
+// chaining over any other implementation, e.g. a microcontroller's AES
+// peripheral (STM32 CRYP, ESP32 AES) already loaded with the KEK.
+
+/// A 128-bit block cipher keyed with the KEK
+pub trait BlockCipher128 {
+    /// Why a block operation failed, e.g. a peripheral timeout or a token
+    /// error; `Infallible` for software ciphers (the built-in AES backends
+    /// use `KeyWrapError`, as OpenSSL and CNG can refuse to run)
+    type Error;
+
+    /// Encrypt one block in place
+    fn encrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Self::Error>;
+
+    /// Decrypt one block in place
+    fn decrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Self::Error>;
+}
+
+macro_rules! impl_block_cipher_128 {
+    ($cipher:ty) => {
+        impl BlockCipher128 for $cipher {
+            type Error = KeyWrapError;
+
+            fn encrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), KeyWrapError> {
+                self.try_encrypt(block)
+            }
+
+            fn decrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), KeyWrapError> {
+                self.try_decrypt(block)
+            }
+        }
+    };
+}
+
+impl_block_cipher_128!(Aes128Ecb);
+impl_block_cipher_128!(Aes192Ecb);
+impl_block_cipher_128!(Aes256Ecb);
+
+// W over `buf` = A | R[1] | ... | R[n], in place. With a single semiblock
+// (RFC5649 short case) it is one plain block encryption. A failing block
+// operation stops W and leaves `buf` part way through (untouched for n = 2).
+pub(crate) fn w<C: BlockCipher128 + ?Sized>(
+    cipher: &mut C,
+    buf: &mut [u8],
+) -> Result<(), C::Error> {
+    let n = buf.len() / 8 - 1;
+    if n == 1 {
+        return cipher.encrypt_block(buf.try_into().expect("one 16-byte block"));
+    }
+    if n == 2 {
+        return w_two_semiblocks(cipher, buf);
+    }
+
+    let mut b = [0u8; 16];
+    for j in 0..6 {
+        for i in 1..n + 1 {
+            b[..8].copy_from_slice(&buf[..8]);
+            b[8..].copy_from_slice(&buf[i * 8..i * 8 + 8]);
+            if let Err(e) = cipher.encrypt_block(&mut b) {
+                b.zeroize();
+                return Err(e);
+            }
+            let t = ((n * j + i) as u64).to_be_bytes();
+            for k in 0..8 {
+                buf[k] = b[k] ^ t[k];
+            }
+            buf[i * 8..i * 8 + 8].copy_from_slice(&b[8..]);
+        }
+    }
+    b.zeroize();
+    Ok(())
+}
+
+// W^-1, the inverse of `w`
+pub(crate) fn w_inv<C: BlockCipher128 + ?Sized>(
+    cipher: &mut C,
+    buf: &mut [u8],
+) -> Result<(), C::Error> {
+    let n = buf.len() / 8 - 1;
+    if n == 1 {
+        return cipher.decrypt_block(buf.try_into().expect("one 16-byte block"));
+    }
+    if n == 2 {
+        return w_inv_two_semiblocks(cipher, buf);
+    }
+
+    let mut b = [0u8; 16];
+    for j in (0..6).rev() {
+        for i in (1..n + 1).rev() {
+            let t = ((n * j + i) as u64).to_be_bytes();
+            for k in 0..8 {
+                b[k] = buf[k] ^ t[k];
+            }
+            b[8..].copy_from_slice(&buf[i * 8..i * 8 + 8]);
+            if let Err(e) = cipher.decrypt_block(&mut b) {
+                b.zeroize();
+                return Err(e);
+            }
+            buf[..8].copy_from_slice(&b[..8]);
+            buf[i * 8..i * 8 + 8].copy_from_slice(&b[8..]);
+        }
+    }
+    b.zeroize();
+    Ok(())
+}
+
+// 128-bit key (n = 2), by far the most common shape: W with A and both R
+// semiblocks held in stack arrays rather than indexed out of `buf`
+fn w_two_semiblocks<C: BlockCipher128 + ?Sized>(
+    cipher: &mut C,
+    buf: &mut [u8],
+) -> Result<(), C::Error> {
+    let mut a = to_u8_8_array(&buf[..8]);
+    let mut r = [to_u8_8_array(&buf[8..16]), to_u8_8_array(&buf[16..24])];
+    let mut b = [0u8; 16];
+    let mut result = Ok(());
+
+    'rounds: for j in 0..6u64 {
+        for (i, r) in r.iter_mut().enumerate() {
+            b[..8].copy_from_slice(&a);
+            b[8..].copy_from_slice(r);
+            if let Err(e) = cipher.encrypt_block(&mut b) {
+                result = Err(e);
+                break 'rounds;
+            }
+            let t = 2 * j + i as u64 + 1;
+            a = (u64::from_be_bytes(to_u8_8_array(&b[..8])) ^ t).to_be_bytes();
+            r.copy_from_slice(&b[8..]);
+        }
+    }
+
+    if result.is_ok() {
+        buf[..8].copy_from_slice(&a);
+        buf[8..16].copy_from_slice(&r[0]);
+        buf[16..24].copy_from_slice(&r[1]);
+    }
+    a.zeroize();
+    r.zeroize();
+    b.zeroize();
+    result
+}
+
+// W^-1 for n = 2, the inverse of `w_two_semiblocks`
+fn w_inv_two_semiblocks<C: BlockCipher128 + ?Sized>(
+    cipher: &mut C,
+    buf: &mut [u8],
+) -> Result<(), C::Error> {
+    let mut a = to_u8_8_array(&buf[..8]);
+    let mut r = [to_u8_8_array(&buf[8..16]), to_u8_8_array(&buf[16..24])];
+    let mut b = [0u8; 16];
+    let mut result = Ok(());
+
+    'rounds: for j in (0..6u64).rev() {
+        for (i, r) in r.iter_mut().enumerate().rev() {
+            let t = 2 * j + i as u64 + 1;
+            b[..8].copy_from_slice(&(u64::from_be_bytes(a) ^ t).to_be_bytes());
+            b[8..].copy_from_slice(r);
+            if let Err(e) = cipher.decrypt_block(&mut b) {
+                result = Err(e);
+                break 'rounds;
+            }
+            a.copy_from_slice(&b[..8]);
+            r.copy_from_slice(&b[8..]);
+        }
+    }
+
+    if result.is_ok() {
+        buf[..8].copy_from_slice(&a);
+        buf[8..16].copy_from_slice(&r[0]);
+        buf[16..24].copy_from_slice(&r[1]);
+    }
+    a.zeroize();
+    r.zeroize();
+    b.zeroize();
+    result
+}
+
+/// Key wrap over a caller-supplied `BlockCipher128`
+///
+/// The cipher holds the KEK (or has it loaded into hardware); this type
+/// supplies only the RFC3394/RFC5649 chaining, framing and checks, with the
+/// same results and errors as the `try_*` and `*_in_place` functions.
+/// A failing block operation is reported as `BlockCipherFailed`, with the
+/// working buffer zeroized; `take_error` returns the cipher's own error.
+///
+/// ```ignore
+/// let mut kw = BlockCipherKw::new(Stm32Cryp::new(dp.CRYP, &kek));
+/// let len = kw.wrap_in_place(&mut buf)?;
+/// ```
+pub struct BlockCipherKw<C: BlockCipher128> {
+    cipher: C,
+    error: Option<C::Error>,
+}
+
+impl<C: BlockCipher128> fmt::Debug for BlockCipherKw<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("BlockCipherKw(..redacted..)")
+    }
+}
+
+impl<C: BlockCipher128> BlockCipherKw<C> {
+    pub fn new(cipher: C) -> Self {
+        BlockCipherKw {
+            cipher,
+            error: None,
+        }
+    }
+
+    /// Give the cipher back, e.g. to release the peripheral
+    pub fn into_inner(self) -> C {
+        self.cipher
+    }
+
+    /// The cipher error behind the last `BlockCipherFailed`, if any
+    pub fn take_error(&mut self) -> Option<C::Error> {
+        self.error.take()
+    }
+
+    fn w(&mut self, buf: &mut [u8]) -> Result<(), KeyWrapError> {
+        self_test::power_up()?;
+        let result = w(&mut self.cipher, buf);
+        self.check(result, buf)
+    }
+
+    fn w_inv(&mut self, buf: &mut [u8]) -> Result<(), KeyWrapError> {
+        self_test::power_up()?;
+        let result = w_inv(&mut self.cipher, buf);
+        self.check(result, buf)
+    }
+
+    // a half-done W or W^-1 still holds key material
+    fn check(&mut self, result: Result<(), C::Error>, buf: &mut [u8]) -> Result<(), KeyWrapError> {
+        result.map_err(|e| {
+            buf.zeroize();
+            self.error = Some(e);
+            KeyWrapError::BlockCipherFailed
+        })
+    }
+
+    /// Wrap key with the IV defined in RFC3394
+    pub fn wrap(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        kw_wrap_with(plaintext, |buf| self.w(buf))
+    }
+
+    /// Unwrap key and Check IV in RFC3394
+    pub fn unwrap(&mut self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        let buf = unwrap_kw_checked(wrapped, |buf| self.w_inv(buf))?;
+        UnwrappedKey::from_slice(&buf[8..])
+    }
+
+    /// Wrap key with pad using padding algorithm (RFC5649)
+    pub fn wrap_with_pad(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        kwp_wrap_with(plaintext, |buf| self.w(buf))
+    }
+
+    /// Unwrap key with pad using padding algorithm (RFC5649)
+    pub fn unwrap_with_pad(&mut self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        let (buf, key_len) = unwrap_kwp_checked(wrapped, |buf| self.w_inv(buf))?;
+        UnwrappedKey::from_slice(&buf[8..8 + key_len])
+    }
+
+    /// Wrap (RFC3394) the plaintext in `buf[8..]` in place, see
+    /// `wrap_key_in_place`
+    pub fn wrap_in_place(&mut self, buf: &mut [u8]) -> Result<usize, KeyWrapError> {
+        kw_wrap_in_place_with(buf, |buf| self.w(buf))
+    }
+
+    /// Unwrap (RFC3394) `buf` in place, see `unwrap_key_in_place`
+    pub fn unwrap_in_place<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], KeyWrapError> {
+        kw_unwrap_in_place_with(buf, |buf| self.w_inv(buf))
+    }
+
+    /// Wrap (RFC5649) the `plaintext_len` octets at `buf[8..]` in place, see
+    /// `wrap_key_with_pad_in_place`
+    pub fn wrap_with_pad_in_place(
+        &mut self,
+        buf: &mut [u8],
+        plaintext_len: usize,
+    ) -> Result<usize, KeyWrapError> {
+        kwp_wrap_in_place_with(buf, plaintext_len, |buf| self.w(buf))
+    }
+
+    /// Unwrap (RFC5649) `buf` in place, see `unwrap_key_with_pad_in_place`
+    pub fn unwrap_with_pad_in_place<'a>(
+        &mut self,
+        buf: &'a mut [u8],
+    ) -> Result<&'a [u8], KeyWrapError> {
+        kwp_unwrap_in_place_with(buf, |buf| self.w_inv(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // stands in for a hardware peripheral, counting the blocks it is fed
+    struct CountingAes {
+        aes: Aes128Ecb,
+        blocks: usize,
+        fail_after: Option<usize>,
+    }
+
+    impl BlockCipher128 for CountingAes {
+        type Error = usize;
+
+        // fails on the block after `fail_after`, reporting the count
+        fn encrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), usize> {
+            self.blocks += 1;
+            if self.fail_after.is_some_and(|n| self.blocks > n) {
+                return Err(self.blocks);
+            }
+            self.aes.try_encrypt(block).map_err(|_| self.blocks)
+        }
+
+        fn decrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), usize> {
+            self.blocks += 1;
+            if self.fail_after.is_some_and(|n| self.blocks > n) {
+                return Err(self.blocks);
+            }
+            self.aes.try_decrypt(block).map_err(|_| self.blocks)
+        }
+    }
+
+    #[test]
+    fn test_block_cipher_kw() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let mut kw = BlockCipherKw::new(CountingAes {
+            aes: Aes128Ecb::try_new(&kek).unwrap(),
+            blocks: 0,
+            fail_after: None,
+        });
+
+        assert_eq!(cipher, kw.wrap(&plain).unwrap());
+        assert_eq!(&plain[..], kw.unwrap(&cipher).unwrap().expose_secret());
+        // 6n block operations each way
+        assert_eq!(24, kw.cipher.blocks);
+
+        let mut buf = [0u8; 24];
+        buf[8..].copy_from_slice(&plain);
+        assert_eq!(24, kw.wrap_in_place(&mut buf).unwrap());
+        assert_eq!(&cipher[..], &buf[..]);
+        assert_eq!(&plain[..], kw.unwrap_in_place(&mut buf).unwrap());
+
+        let mut tampered = cipher.clone();
+        tampered[10] ^= 1;
+        assert_eq!(
+            Err(KeyWrapError::IntegrityCheckFailed),
+            kw.unwrap_in_place(&mut tampered)
+        );
+        assert_eq!(vec![0u8; 24], tampered);
+
+        // RFC5649 short case is a single block operation
+        let mut buf = [0u8; 16];
+        buf[8..13].copy_from_slice(b"short");
+        let before = kw.cipher.blocks;
+        assert_eq!(16, kw.wrap_with_pad_in_place(&mut buf, 5).unwrap());
+        assert_eq!(before + 1, kw.cipher.blocks);
+        assert_eq!(b"short", kw.unwrap_with_pad_in_place(&mut buf).unwrap());
+
+        let wrapped = kw.wrap_with_pad(b"a longer padded key").unwrap();
+        assert_eq!(
+            b"a longer padded key",
+            kw.unwrap_with_pad(&wrapped).unwrap().expose_secret()
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidCiphertextLength(8)),
+            kw.unwrap_with_pad(&[0u8; 8]).map(|_| ())
+        );
+
+        // a block operation failing part way through W clears the buffer
+        kw.cipher.fail_after = Some(kw.cipher.blocks + 5);
+        let mut buf = [0u8; 24];
+        buf[8..].copy_from_slice(&plain);
+        assert_eq!(
+            Err(KeyWrapError::BlockCipherFailed),
+            kw.wrap_in_place(&mut buf)
+        );
+        assert_eq!([0u8; 24], buf);
+        assert_eq!(Some(kw.cipher.blocks), kw.take_error());
+        assert_eq!(None, kw.take_error());
+        assert_eq!(
+            Err(KeyWrapError::BlockCipherFailed),
+            kw.unwrap(&cipher).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Big-endian conversions between semiblocks and bytes
+//!
+//! Key wrap treats its data as a sequence of 64-bit big-endian semiblocks
+//! (RFC3394 section 2). These helpers convert in both directions without
+//! depending on host endianness or on the alignment of the input.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+/// Serialize `words` as consecutive big-endian semiblocks
+/// (`8 * words.len()` bytes)
+pub fn u64s_to_be_bytes(words: &[u64]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(words.len() * 8);
+    for w in words {
+        bytes.extend_from_slice(&w.to_be_bytes());
+    }
+    bytes
+}
+
+/// Parse `bytes` as big-endian semiblocks; the length must be a multiple
+/// of 8
+pub fn be_bytes_to_u64s(bytes: &[u8]) -> Result<Vec<u64>, String> {
+    if !bytes.len().is_multiple_of(8) {
+        return Err(format!("Length is not a multiple of 8: {}", bytes.len()));
+    }
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|c| u64::from_be_bytes(to_u8_8_array(c)))
+        .collect())
+}
+
+#[inline(always)]
+pub(crate) fn to_u8_8_array(array: &[u8]) -> [u8; 8] {
+    array.try_into().expect("semiblock must be 8 bytes")
+}
+
+#[inline(always)]
+pub(crate) fn to_u8_4_array(array: &[u8]) -> [u8; 4] {
+    array.try_into().expect("half semiblock must be 4 bytes")
+}
+
+#[inline(always)]
+pub(crate) fn u32_from_be_u8(buffer: &[u8; 4]) -> u32 {
+    u32::from_be_bytes(*buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semiblock_round_trip() {
+        let words = [0xA6A6A6A6A6A6A6A6, 0x0011223344556677, 0];
+        let bytes = u64s_to_be_bytes(&words);
+        assert_eq!(
+            hex::decode("A6A6A6A6A6A6A6A600112233445566770000000000000000").unwrap(),
+            bytes
+        );
+        assert_eq!(words.to_vec(), be_bytes_to_u64s(&bytes).unwrap());
+        // input at an odd address
+        let shifted = [&[0u8][..], &bytes].concat();
+        assert_eq!(words.to_vec(), be_bytes_to_u64s(&shifted[1..]).unwrap());
+        assert!(be_bytes_to_u64s(&bytes[1..]).is_err());
+        assert!(u64s_to_be_bytes(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_be_helpers() {
+        assert_eq!(
+            0xA65959A6,
+            u32_from_be_u8(&to_u8_4_array(&[0xa6, 0x59, 0x59, 0xa6]))
+        );
+    }
+}
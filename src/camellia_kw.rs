@@ -0,0 +1,95 @@
+use crate::{Kek, KeyWrapError, UnwrappedKey};
+use alloc::string::String;
+use alloc::vec::Vec;
+use camellia::{Camellia128, Camellia192, Camellia256};
+
+// Camellia key wrap (RFC 3657, `camellia-kw` feature)
+//
+// RFC 3657 is RFC3394 with Camellia in place of AES: same ICV, same six
+// rounds of W over 64-bit semiblocks, for CMS and Japanese government
+// profiles that mandate Camellia. The chaining is `Kek<C>`'s.
+
+/// id-camellia128-wrap (RFC 3657 Section 4)
+pub const ID_CAMELLIA128_WRAP: &str = "1.2.392.200011.61.1.1.3.2";
+/// id-camellia192-wrap (RFC 3657 Section 4)
+pub const ID_CAMELLIA192_WRAP: &str = "1.2.392.200011.61.1.1.3.3";
+/// id-camellia256-wrap (RFC 3657 Section 4)
+pub const ID_CAMELLIA256_WRAP: &str = "1.2.392.200011.61.1.1.3.4";
+
+/// Wrap key with Camellia and the IV defined in RFC3394 (RFC 3657)
+pub fn try_camellia_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    match kek.len() {
+        16 => Kek::<Camellia128>::new_from_slice(kek)?.wrap(plaintext),
+        24 => Kek::<Camellia192>::new_from_slice(kek)?.wrap(plaintext),
+        32 => Kek::<Camellia256>::new_from_slice(kek)?.wrap(plaintext),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+/// Unwrap key with Camellia and Check IV (RFC 3657)
+pub fn try_camellia_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    match kek.len() {
+        16 => Kek::<Camellia128>::new_from_slice(kek)?.unwrap(wrapped),
+        24 => Kek::<Camellia192>::new_from_slice(kek)?.unwrap(wrapped),
+        32 => Kek::<Camellia256>::new_from_slice(kek)?.unwrap(wrapped),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+/// Wrap key with Camellia and the IV defined in RFC3394 (RFC 3657)
+pub fn camellia_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_camellia_wrap_key(kek, plaintext)?)
+}
+
+/// Unwrap key with Camellia and Check IV (RFC 3657)
+pub fn camellia_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_camellia_unwrap_key(kek, wrapped)?
+        .expose_secret()
+        .to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 3657 Section 4
+    #[test]
+    fn test_rfc3657_vectors() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F")
+            .unwrap();
+        for (kek_len, plain_len, cipher) in [
+            (16, 16, "635D6AC46EEDEBD3A7F4A06421A4CBD1746B24795BA2F708"),
+            (24, 16, "FE8F5C4E2164CDFE36233C9F898F93DF6E6F1D892D187742"),
+            (32, 16, "B43E6793EE3B35B7698253B26BAD0CA2D5E7793C6F5DDD48"),
+            (
+                24,
+                24,
+                "EA7B7515BDE2F268849FA2B4D96ADBACC8111073D463DA9FB5E7648F6DD2FE76",
+            ),
+            (
+                32,
+                24,
+                "C7CB865E14A7DC00B339F9D9041ED4C3BA4E34EEDADD7A1C5F98534180CD59BE",
+            ),
+            (
+                32,
+                32,
+                "96A502A1E0C12700EC01D9E9B3688D50B7AE25FBAE06DD18F0E30092AC1ABD5BC7575DA930DF1636",
+            ),
+        ]
+        .iter()
+        {
+            let kek = &kek[..*kek_len];
+            let plain = &plain[..*plain_len];
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(cipher, camellia_wrap_key(kek, plain).unwrap());
+            assert_eq!(plain, &camellia_unwrap_key(kek, &cipher).unwrap()[..]);
+        }
+        assert_eq!(
+            KeyWrapError::InvalidKekLength(20),
+            try_camellia_wrap_key(&kek[..20], &plain).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::locked::locked_copy;
+use crate::{self_test, KeyWrapError, UnwrappedKey};
+use alloc::vec::Vec;
+use chacha20poly1305::aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce, Tag};
+use hkdf::Hkdf;
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+// ChaCha20-Poly1305 wrapping (RFC 8439, `chacha` feature)
+//
+// For cores without AES hardware (Cortex-M0/M0+, older ARM), where
+// ChaCha20's add-rotate-xor rounds are far cheaper than constant-time
+// software AES. The KEK itself never keys anything: HKDF-SHA256(ikm = KEK)
+// is expanded into a cipher key and a separate nonce key. The wrapped form
+// is nonce (12) | ciphertext | tag (16) and the nonce is either random or
+// derived, SIV-style, as
+//   HKDF-Expand(PRK = nonce key, info = len(AAD) (u64 BE) | AAD | key)
+// so wrapping needs no RNG and, like KW, only reveals whether two wraps had
+// the same input. Unwrapping reads the nonce from the blob either way.
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const CIPHER_KEY_INFO: &[u8] = b"aes-keywrap-rs chacha20poly1305 cipher key";
+const NONCE_KEY_INFO: &[u8] = b"aes-keywrap-rs chacha20poly1305 nonce key";
+
+// 256-bit subkey of the KEK for one purpose
+fn subkey(kek: &[u8], info: &[u8]) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(None, kek)
+        .expand(info, &mut key[..])
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn cipher(kek: &[u8]) -> Result<ChaCha20Poly1305, KeyWrapError> {
+    if kek.len() != 32 {
+        return Err(KeyWrapError::InvalidKekLength(kek.len()));
+    }
+    Ok(ChaCha20Poly1305::new(
+        &(*subkey(kek, CIPHER_KEY_INFO)).into(),
+    ))
+}
+
+fn seal(
+    kek: &[u8],
+    nonce: &[u8; NONCE_LEN],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, KeyWrapError> {
+    let cipher = cipher(kek)?;
+    let mut buf: Vec<u8> = Vec::with_capacity(NONCE_LEN + plaintext.len() + TAG_LEN);
+    buf.extend_from_slice(nonce);
+    buf.extend_from_slice(plaintext);
+    let tag = cipher
+        .encrypt_in_place_detached(Nonce::from_slice(nonce), aad, &mut buf[NONCE_LEN..])
+        .map_err(|_| KeyWrapError::LengthOverflow(plaintext.len()))?;
+    buf.extend_from_slice(&tag);
+    Ok(buf)
+}
+
+fn check_wrap_input(kek: &[u8], plaintext: &[u8]) -> Result<(), KeyWrapError> {
+    if kek.len() != 32 {
+        return Err(KeyWrapError::InvalidKekLength(kek.len()));
+    }
+    if plaintext.is_empty() {
+        return Err(KeyWrapError::InvalidPlaintextLength(0));
+    }
+    self_test::power_up()
+}
+
+/// Wrap key with ChaCha20-Poly1305 under a 256-bit KEK, with the nonce
+/// derived from the KEK, `aad` and the key (deterministic)
+pub fn try_chacha20poly1305_wrap_key(
+    kek: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, KeyWrapError> {
+    check_wrap_input(kek, plaintext)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    Hkdf::<Sha256>::from_prk(&subkey(kek, NONCE_KEY_INFO)[..])
+        .expect("32 bytes is a valid HKDF-SHA256 PRK")
+        .expand_multi_info(
+            &[&(aad.len() as u64).to_be_bytes(), aad, plaintext],
+            &mut nonce,
+        )
+        .expect("12 bytes is a valid HKDF-SHA256 output length");
+    seal(kek, &nonce, plaintext, aad)
+}
+
+/// Wrap key with ChaCha20-Poly1305, drawing the nonce from `rng`
+pub fn try_chacha20poly1305_wrap_key_with_rng<R: CryptoRng + RngCore>(
+    kek: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, KeyWrapError> {
+    check_wrap_input(kek, plaintext)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    seal(kek, &nonce, plaintext, aad)
+}
+
+/// Unwrap a nonce | ciphertext | tag blob and check the tag over it and
+/// `aad`
+pub fn try_chacha20poly1305_unwrap_key(
+    kek: &[u8],
+    wrapped: &[u8],
+    aad: &[u8],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    let cipher = cipher(kek)?;
+    if wrapped.len() <= NONCE_LEN + TAG_LEN {
+        return Err(KeyWrapError::InvalidCiphertextLength(wrapped.len()));
+    }
+    self_test::power_up()?;
+
+    let (nonce, rest) = wrapped.split_at(NONCE_LEN);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+    let mut buf = locked_copy(ciphertext)?;
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::from_slice(nonce),
+            aad,
+            &mut buf,
+            Tag::from_slice(tag),
+        )
+        .map_err(|_| KeyWrapError::IntegrityCheckFailed)?;
+    UnwrappedKey::from_slice(&buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::CountingRng;
+
+    #[test]
+    fn test_chacha20poly1305() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let random =
+            try_chacha20poly1305_wrap_key_with_rng(&kek, &plain, b"", &mut CountingRng(0)).unwrap();
+        for (aad, cipher) in [
+            (&b""[..], "B2A983CD3545B87E8373E8EBA0A8BAE80199A55D9B691DD8CFFA40F66361722F724DC256A326FDF05CD799D6"),
+            (&b"aad"[..], "255F05B2FC256107752CEC75AC2C56E615785CB7C76B17D709AC31315AB4753840A918E1DBF1606218500E96"),
+        ]
+        .iter()
+        {
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(cipher, try_chacha20poly1305_wrap_key(&kek, &plain, aad).unwrap());
+            assert_eq!(
+                &plain[..],
+                try_chacha20poly1305_unwrap_key(&kek, &cipher, aad)
+                    .unwrap()
+                    .expose_secret()
+            );
+            assert_eq!(
+                KeyWrapError::IntegrityCheckFailed,
+                try_chacha20poly1305_unwrap_key(&kek, &cipher, b"other").unwrap_err()
+            );
+        }
+
+        assert_eq!(
+            hex::decode("000102030405060708090A0BB91F06443C0C35DC88E35A3022BF7BD7DF09482946F22C968C05BDA4B9D57441").unwrap(),
+            random
+        );
+        assert_eq!(
+            &plain[..],
+            try_chacha20poly1305_unwrap_key(&kek, &random, b"")
+                .unwrap()
+                .expose_secret()
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{BlockCipher128, BlockCipherKw, KeyWrapError, UnwrappedKey};
+use alloc::vec::Vec;
+use cipher::consts::U16;
+use cipher::generic_array::GenericArray;
+use cipher::{BlockDecrypt, BlockEncrypt, BlockSizeUser, KeyInit};
+use core::convert::Infallible;
+use core::fmt;
+
+// Key wrap over RustCrypto `cipher` block ciphers (`cipher` feature)
+//
+// Any 128-bit `BlockEncrypt + BlockDecrypt` can carry the RFC3394/RFC5649
+// chaining: `aes::Aes256` (AES-NI / ARMv8-CE / fixsliced), a vendor cipher
+// implementing the same traits, or another 128-bit cipher altogether. The
+// `cipher` traits encrypt through `&self`, so a `Kek<C>` can be shared
+// between threads like the `Aes*Kw` contexts.
+
+/// KEK held as a keyed RustCrypto block cipher
+///
+/// ```ignore
+/// let kek = Kek::<aes::Aes256>::new_from_slice(&kek_bytes)?;
+/// let wrapped = kek.wrap_with_pad(&dek)?;
+/// ```
+pub struct Kek<C> {
+    cipher: C,
+}
+
+impl<C> fmt::Debug for Kek<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Kek(..redacted..)")
+    }
+}
+
+// `BlockCipher128` view of a borrowed `cipher` block cipher
+struct CipherRef<'a, C>(&'a C);
+
+impl<C> BlockCipher128 for CipherRef<'_, C>
+where
+    C: BlockEncrypt + BlockDecrypt + BlockSizeUser<BlockSize = U16>,
+{
+    type Error = Infallible;
+
+    fn encrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Infallible> {
+        self.0.encrypt_block(GenericArray::from_mut_slice(block));
+        Ok(())
+    }
+
+    fn decrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Infallible> {
+        self.0.decrypt_block(GenericArray::from_mut_slice(block));
+        Ok(())
+    }
+}
+
+impl<C> Kek<C>
+where
+    C: BlockEncrypt + BlockDecrypt + BlockSizeUser<BlockSize = U16>,
+{
+    /// Wrap an already keyed cipher
+    pub fn new(cipher: C) -> Self {
+        Kek { cipher }
+    }
+
+    fn kw(&self) -> BlockCipherKw<CipherRef<'_, C>> {
+        BlockCipherKw::new(CipherRef(&self.cipher))
+    }
+
+    /// Wrap key with the IV defined in RFC3394
+    pub fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.kw().wrap(plaintext)
+    }
+
+    /// Unwrap key and Check IV in RFC3394
+    pub fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        self.kw().unwrap(wrapped)
+    }
+
+    /// Wrap key with pad using padding algorithm (RFC5649)
+    pub fn wrap_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.kw().wrap_with_pad(plaintext)
+    }
+
+    /// Unwrap key with pad using padding algorithm (RFC5649)
+    pub fn unwrap_with_pad(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        self.kw().unwrap_with_pad(wrapped)
+    }
+
+    /// Wrap (RFC3394) the plaintext in `buf[8..]` in place, see
+    /// `wrap_key_in_place`
+    pub fn wrap_in_place(&self, buf: &mut [u8]) -> Result<usize, KeyWrapError> {
+        self.kw().wrap_in_place(buf)
+    }
+
+    /// Unwrap (RFC3394) `buf` in place, see `unwrap_key_in_place`
+    pub fn unwrap_in_place<'a>(&self, buf: &'a mut [u8]) -> Result<&'a [u8], KeyWrapError> {
+        self.kw().unwrap_in_place(buf)
+    }
+
+    /// Wrap (RFC5649) the `plaintext_len` octets at `buf[8..]` in place, see
+    /// `wrap_key_with_pad_in_place`
+    pub fn wrap_with_pad_in_place(
+        &self,
+        buf: &mut [u8],
+        plaintext_len: usize,
+    ) -> Result<usize, KeyWrapError> {
+        self.kw().wrap_with_pad_in_place(buf, plaintext_len)
+    }
+
+    /// Unwrap (RFC5649) `buf` in place, see `unwrap_key_with_pad_in_place`
+    pub fn unwrap_with_pad_in_place<'a>(
+        &self,
+        buf: &'a mut [u8],
+    ) -> Result<&'a [u8], KeyWrapError> {
+        self.kw().unwrap_with_pad_in_place(buf)
+    }
+}
+
+impl<C> Kek<C>
+where
+    C: BlockEncrypt + BlockDecrypt + BlockSizeUser<BlockSize = U16> + KeyInit,
+{
+    /// Key the cipher with `kek`, which must be the cipher's key size
+    pub fn new_from_slice(kek: &[u8]) -> Result<Self, KeyWrapError> {
+        C::new_from_slice(kek)
+            .map(Kek::new)
+            .map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cipher_kek() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F")
+            .unwrap();
+        let cipher = hex::decode(
+            "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21",
+        )
+        .unwrap();
+        let k = Kek::<aes::Aes256>::new_from_slice(&kek).unwrap();
+        assert_eq!(cipher, k.wrap(&plain).unwrap());
+        assert_eq!(&plain[..], k.unwrap(&cipher).unwrap().expose_secret());
+        assert_eq!("Kek(..redacted..)", format!("{:?}", k));
+
+        // RFC5649 192-bit KEK vectors
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let k = Kek::new(aes::Aes192::new_from_slice(&kek).unwrap());
+        let mut buf = [0u8; 16];
+        buf[8..15].copy_from_slice(&hex::decode("466F7250617369").unwrap());
+        assert_eq!(16, k.wrap_with_pad_in_place(&mut buf, 7).unwrap());
+        assert_eq!(
+            &hex::decode("AFBEB0F07DFBF5419200F2CCB50BB24F").unwrap()[..],
+            &buf[..]
+        );
+        assert_eq!(b"ForPasi", k.unwrap_with_pad_in_place(&mut buf).unwrap());
+
+        assert_eq!(
+            Some(KeyWrapError::InvalidKekLength(16)),
+            Kek::<aes::Aes256>::new_from_slice(&[0u8; 16]).err()
+        );
+    }
+}
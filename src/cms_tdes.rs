@@ -0,0 +1,96 @@
+use crate::locked::{locked_copy, LockedBuf};
+use crate::KeyWrapError;
+use alloc::vec::Vec;
+use des::cipher::generic_array::GenericArray;
+use des::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use des::TdesEde3;
+use rand_core::{CryptoRng, RngCore};
+use sha1::{Digest, Sha1};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+// RFC 3217 CMS Triple-DES key wrap core, shared by id-alg-HMACwith3DESwrap
+// (`hmac-wrap`) and X9.102 AKW1 (`akw`): SHA-1 checksum, two CBC passes with
+// a random IV and a byte reversal. Callers check the data length.
+
+// RFC 3217 Section 3.1 step 8
+const IV_3217: [u8; 8] = [0x4a, 0xdd, 0xa2, 0x2c, 0x79, 0xe8, 0x21, 0x05];
+
+fn tdes(kek: &[u8]) -> Result<TdesEde3, KeyWrapError> {
+    TdesEde3::new_from_slice(kek).map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))
+}
+
+// RFC 3217 Section 2, first 8 octets of SHA-1
+fn checksum(data: &[u8]) -> [u8; 8] {
+    let mut icv = [0u8; 8];
+    icv.copy_from_slice(&Sha1::digest(data)[..8]);
+    icv
+}
+
+fn cbc_encrypt(cipher: &TdesEde3, iv: &[u8], buf: &mut [u8]) {
+    let mut prev = GenericArray::clone_from_slice(iv);
+    for block in buf.chunks_exact_mut(8) {
+        for (p, b) in prev.iter_mut().zip(block.iter()) {
+            *p ^= b;
+        }
+        cipher.encrypt_block(&mut prev);
+        block.copy_from_slice(&prev);
+    }
+    prev.zeroize();
+}
+
+fn cbc_decrypt(cipher: &TdesEde3, iv: &[u8], buf: &mut [u8]) {
+    let mut prev = [0u8; 8];
+    prev.copy_from_slice(iv);
+    let mut b = GenericArray::from([0u8; 8]);
+    for block in buf.chunks_exact_mut(8) {
+        b.copy_from_slice(block);
+        cipher.decrypt_block(&mut b);
+        for k in 0..8 {
+            let c = block[k];
+            block[k] = b[k] ^ prev[k];
+            prev[k] = c;
+        }
+    }
+    b.zeroize();
+}
+
+// `data` must be a non-empty multiple of 8 octets
+pub(crate) fn wrap<R: CryptoRng + RngCore>(
+    kek: &[u8],
+    data: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, KeyWrapError> {
+    let cipher = tdes(kek)?;
+    let mut iv = [0u8; 8];
+    rng.fill_bytes(&mut iv);
+
+    // IV | CBC(DATA | ICV), reversed, then CBC under the fixed IV
+    let mut buf: Vec<u8> = Vec::with_capacity(data.len() + 16);
+    buf.extend_from_slice(&iv);
+    buf.extend_from_slice(data);
+    buf.extend_from_slice(&checksum(data));
+    cbc_encrypt(&cipher, &iv, &mut buf[8..]);
+    buf.reverse();
+    cbc_encrypt(&cipher, &IV_3217, &mut buf);
+    Ok(buf)
+}
+
+// IV | DATA | ICV in a locked buffer; the DATA part is `[8..len - 8]`
+pub(crate) fn unwrap(kek: &[u8], wrapped: &[u8]) -> Result<LockedBuf, KeyWrapError> {
+    let cipher = tdes(kek)?;
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err(KeyWrapError::InvalidCiphertextLength(wrapped.len()));
+    }
+
+    let mut buf = locked_copy(wrapped)?;
+    cbc_decrypt(&cipher, &IV_3217, &mut buf);
+    buf.reverse();
+    let (iv, rest) = buf.split_at_mut(8);
+    cbc_decrypt(&cipher, iv, rest);
+    let (data, icv) = rest.split_at(rest.len() - 8);
+    if !bool::from(icv.ct_eq(&checksum(data))) {
+        return Err(KeyWrapError::IntegrityCheckFailed);
+    }
+    Ok(buf)
+}
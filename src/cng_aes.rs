@@ -0,0 +1,149 @@
+// AES block function from Windows CNG (`backend-cng` feature)
+//
+// Block encryptions go through BCryptEncrypt / BCryptDecrypt with an
+// AES-ECB key object, so the OS crypto provider's AES acceleration and
+// policy (e.g. FIPS mode) apply while this crate supplies the RFC3394/RFC5649
+// chaining. The CNG calls are wrapped by `win-crypto-ng`, keeping this crate
+// free of unsafe code.
+//
+// The types follow crypto2's `blockmode::AesXxxEcb` API (new/encrypt/decrypt)
+// like `soft_aes`, except that each call returns `BlockCipherFailed` when
+// the provider refuses (e.g. unavailable, or key import denied by policy)
+// rather than panicking. A CNG key handle can't be cloned through
+// `win-crypto-ng`, so `try_clone` imports the KEK copy kept alongside it again.
+
+use crate::KeyWrapError;
+use win_crypto_ng::symmetric::{
+    ChainingMode, SymmetricAlgorithm, SymmetricAlgorithmId, SymmetricAlgorithmKey,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+const BLOCK_LEN: usize = 16;
+
+macro_rules! impl_cng_ecb {
+    ($name:tt, $key_len:expr) => {
+        pub struct $name {
+            // dropped before the algorithm provider it was created from
+            handle: SymmetricAlgorithmKey,
+            _algorithm: SymmetricAlgorithm,
+            key: Zeroizing<[u8; $key_len]>,
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize = $key_len;
+
+            pub fn new(key: &[u8]) -> Result<Self, KeyWrapError> {
+                assert_eq!(key.len(), Self::KEY_LEN);
+
+                let algorithm =
+                    SymmetricAlgorithm::open(SymmetricAlgorithmId::Aes, ChainingMode::Ecb)
+                        .map_err(|_| KeyWrapError::BlockCipherFailed)?;
+                let handle = algorithm
+                    .new_key(key)
+                    .map_err(|_| KeyWrapError::BlockCipherFailed)?;
+                let mut k = Zeroizing::new([0u8; $key_len]);
+                k.copy_from_slice(key);
+                Ok(Self {
+                    handle,
+                    _algorithm: algorithm,
+                    key: k,
+                })
+            }
+
+            pub fn try_clone(&self) -> Result<Self, KeyWrapError> {
+                Self::new(&self.key[..])
+            }
+
+            pub fn encrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                assert_eq!(blocks.len() % BLOCK_LEN, 0);
+
+                match self.handle.encrypt(None, blocks, None) {
+                    Ok(out) if out.as_slice().len() == blocks.len() => {
+                        blocks.copy_from_slice(out.as_slice());
+                        Ok(())
+                    }
+                    _ => {
+                        blocks.zeroize();
+                        Err(KeyWrapError::BlockCipherFailed)
+                    }
+                }
+            }
+
+            pub fn decrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                assert_eq!(blocks.len() % BLOCK_LEN, 0);
+
+                match self.handle.decrypt(None, blocks, None) {
+                    Ok(out) => {
+                        // plaintext key material; scrubbed even without
+                        // win-crypto-ng's own `zeroize` feature
+                        let out = Zeroizing::new(out.into_inner());
+                        if out.len() == blocks.len() {
+                            blocks.copy_from_slice(&out);
+                            return Ok(());
+                        }
+                    }
+                    Err(_) => {}
+                }
+                blocks.zeroize();
+                Err(KeyWrapError::BlockCipherFailed)
+            }
+        }
+    };
+}
+
+impl_cng_ecb!(Aes128Ecb, 16);
+impl_cng_ecb!(Aes192Ecb, 24);
+impl_cng_ecb!(Aes256Ecb, 32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FIPS 197 appendix C
+    #[test]
+    fn test_fips197_vectors() {
+        let plain = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        for (key, cipher) in [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ]
+        .iter()
+        {
+            let key = hex::decode(key).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            let mut blocks = plain.repeat(3);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).unwrap().encrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).unwrap().encrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key)
+                    .unwrap()
+                    .try_clone()
+                    .unwrap()
+                    .encrypt(&mut blocks),
+            }
+            .unwrap();
+            assert_eq!(cipher.repeat(3), blocks);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).unwrap().decrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).unwrap().decrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key)
+                    .unwrap()
+                    .try_clone()
+                    .unwrap()
+                    .decrypt(&mut blocks),
+            }
+            .unwrap();
+            assert_eq!(plain.repeat(3), blocks);
+        }
+    }
+}
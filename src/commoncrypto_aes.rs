@@ -0,0 +1,129 @@
+// AES block function from Apple's CommonCrypto (`backend-commoncrypto` feature)
+//
+// Block encryptions go through CCCryptor in AES-ECB mode without padding, so
+// macOS / iOS apps stay on the platform's crypto (corecrypto) as platform
+// policy asks, while this crate supplies the RFC3394/RFC5649 chaining. The
+// CommonCrypto calls are wrapped by `common-crypto`, keeping this crate free
+// of unsafe code.
+//
+// The types follow crypto2's `blockmode::AesXxxEcb` API (new/encrypt/decrypt)
+// like `soft_aes`, except that encrypt/decrypt return `BlockCipherFailed`
+// when CCCryptor refuses rather than panicking. A CCCryptor handle isn't
+// Send, so only the KEK is kept and each call creates its own cryptor.
+
+use crate::KeyWrapError;
+use common_crypto::cryptor::{Config, Cryptor, Mode};
+use zeroize::{Zeroize, Zeroizing};
+
+const BLOCK_LEN: usize = 16;
+
+macro_rules! impl_commoncrypto_ecb {
+    ($name:tt, $config:ident, $key_len:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            key: Zeroizing<[u8; $key_len]>,
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize = $key_len;
+
+            pub fn new(key: &[u8]) -> Self {
+                assert_eq!(key.len(), Self::KEY_LEN);
+
+                let mut k = Zeroizing::new([0u8; $key_len]);
+                k.copy_from_slice(key);
+                Self { key: k }
+            }
+
+            fn config(&self) -> Config<'_> {
+                Config::$config {
+                    mode: Mode::ECB,
+                    iv: None,
+                    key: &self.key,
+                }
+            }
+
+            pub fn encrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                assert_eq!(blocks.len() % BLOCK_LEN, 0);
+
+                match Cryptor::encrypt(&self.config(), &blocks[..]) {
+                    Ok(out) => {
+                        let out = Zeroizing::new(out);
+                        if out.len() == blocks.len() {
+                            blocks.copy_from_slice(&out);
+                            return Ok(());
+                        }
+                    }
+                    Err(_) => {}
+                }
+                blocks.zeroize();
+                Err(KeyWrapError::BlockCipherFailed)
+            }
+
+            pub fn decrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                assert_eq!(blocks.len() % BLOCK_LEN, 0);
+
+                match Cryptor::decrypt(&self.config(), &blocks[..]) {
+                    Ok(out) => {
+                        let out = Zeroizing::new(out);
+                        if out.len() == blocks.len() {
+                            blocks.copy_from_slice(&out);
+                            return Ok(());
+                        }
+                    }
+                    Err(_) => {}
+                }
+                blocks.zeroize();
+                Err(KeyWrapError::BlockCipherFailed)
+            }
+        }
+    };
+}
+
+impl_commoncrypto_ecb!(Aes128Ecb, AES128, 16);
+impl_commoncrypto_ecb!(Aes192Ecb, AES192, 24);
+impl_commoncrypto_ecb!(Aes256Ecb, AES256, 32);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FIPS 197 appendix C
+    #[test]
+    fn test_fips197_vectors() {
+        let plain = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        for (key, cipher) in [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ]
+        .iter()
+        {
+            let key = hex::decode(key).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            let mut blocks = plain.repeat(3);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).encrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).encrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key).clone().encrypt(&mut blocks),
+            }
+            .unwrap();
+            assert_eq!(cipher.repeat(3), blocks);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).decrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).decrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key).clone().decrypt(&mut blocks),
+            }
+            .unwrap();
+            assert_eq!(plain.repeat(3), blocks);
+        }
+    }
+}
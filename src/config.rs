@@ -0,0 +1,105 @@
+use crate::{EnvSecret, KeyWrapper, ZeroizingString, ENV_TOKEN_PREFIX};
+use serde::de::{Deserialize, Deserializer, Error};
+use std::sync::{Arc, RwLock};
+
+// Wrapped values inline in configuration files
+//
+//     #[derive(Deserialize)]
+//     struct Settings {
+//         #[serde(deserialize_with = "aes_keywrap_rs::deserialize_wrapped")]
+//         database_url: ZeroizingString,
+//         api_token: ConfigSecret,
+//     }
+//
+// Strings carrying the `kwp.` token prefix are unwrapped during
+// deserialization with the registered key wrapper; other strings pass
+// through unchanged so plaintext development configs keep working.
+
+static CONFIG_KEY_WRAPPER: RwLock<Option<Arc<dyn KeyWrapper + Send + Sync>>> = RwLock::new(None);
+
+/// Register the key wrapper used to unwrap tokens in configuration values
+pub fn register_config_key_wrapper(wrapper: Arc<dyn KeyWrapper + Send + Sync>) {
+    *CONFIG_KEY_WRAPPER.write().unwrap() = Some(wrapper);
+}
+
+/// Forget the registered key wrapper
+pub fn clear_config_key_wrapper() {
+    *CONFIG_KEY_WRAPPER.write().unwrap() = None;
+}
+
+fn unwrap_config_value(value: String) -> Result<ZeroizingString, String> {
+    if !value.starts_with(ENV_TOKEN_PREFIX) {
+        return Ok(ZeroizingString::from(value));
+    }
+
+    let wrapper = CONFIG_KEY_WRAPPER
+        .read()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| String::from("No key wrapper registered for wrapped config values"))?;
+    EnvSecret::decode(&*wrapper, &value)
+}
+
+/// Configuration string that may be given as a wrapped token
+#[derive(Debug)]
+pub struct ConfigSecret(ZeroizingString);
+
+impl ConfigSecret {
+    pub fn expose_secret(&self) -> &str {
+        self.0.expose_secret()
+    }
+
+    pub fn into_inner(self) -> ZeroizingString {
+        self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for ConfigSecret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        unwrap_config_value(value)
+            .map(ConfigSecret)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// `#[serde(deserialize_with = ...)]` adapter producing a `ZeroizingString`
+pub fn deserialize_wrapped<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ZeroizingString, D::Error> {
+    ConfigSecret::deserialize(deserializer).map(ConfigSecret::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AesKeyWrapper, Algorithm};
+    use serde::de::value::{Error as ValueError, StrDeserializer};
+    use serde::de::IntoDeserializer;
+
+    fn from_str(s: &str) -> Result<ConfigSecret, ValueError> {
+        let deserializer: StrDeserializer<ValueError> = s.into_deserializer();
+        ConfigSecret::deserialize(deserializer)
+    }
+
+    #[test]
+    fn test_config_secret_deserialize() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapper = AesKeyWrapper::new(&kek, Algorithm::Kwp).unwrap();
+        let token = EnvSecret::encode(&wrapper, "hunter2").unwrap();
+
+        clear_config_key_wrapper();
+        assert!(from_str(&token).is_err());
+        assert_eq!("plain", from_str("plain").unwrap().expose_secret());
+
+        register_config_key_wrapper(Arc::new(wrapper));
+        assert_eq!("hunter2", from_str(&token).unwrap().expose_secret());
+        let deserializer: StrDeserializer<ValueError> = token.as_str().into_deserializer();
+        assert_eq!(
+            "hunter2",
+            deserialize_wrapped(deserializer).unwrap().expose_secret()
+        );
+        assert!(from_str(&format!("kwp.{}", "00".repeat(24))).is_err());
+        clear_config_key_wrapper();
+    }
+}
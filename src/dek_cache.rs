@@ -0,0 +1,186 @@
+use crate::{Algorithm, KeyWrapError, KeyWrapper, UnwrappedKey};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+// Unwrapped key, overwritten with zeros when the entry is evicted or replaced
+struct CachedDek {
+    dek: Zeroizing<Vec<u8>>,
+    expires_at: Instant,
+    last_used: Instant,
+}
+
+/// `KeyWrapper` decorator caching unwrapped DEKs
+///
+/// Entries are keyed by the wrapped blob itself (it is not secret and is
+/// about the size of a digest, so there is nothing to gain from hashing it
+/// and no collision to handle), live for `ttl` after they were unwrapped,
+/// and the least recently used entry is evicted once `max_entries` is
+/// reached. Evicted plaintext is zeroized. Failed unwraps are never cached.
+pub struct DekCache<W: KeyWrapper> {
+    inner: W,
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<Vec<u8>, CachedDek>>,
+}
+
+impl<W: KeyWrapper> DekCache<W> {
+    pub fn new(inner: W, ttl: Duration, max_entries: usize) -> DekCache<W> {
+        DekCache {
+            inner,
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Number of cached entries, including expired ones not yet evicted
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop (and zeroize) every cached DEK
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn insert(&self, wrapped: &[u8], dek: &[u8]) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, e| e.expires_at > now);
+        while entries.len() >= self.max_entries {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(k) => entries.remove(&k),
+                None => break,
+            };
+        }
+        entries.insert(
+            wrapped.to_vec(),
+            CachedDek {
+                dek: Zeroizing::new(dek.to_vec()),
+                expires_at: now + self.ttl,
+                last_used: now,
+            },
+        );
+    }
+}
+
+impl<W: KeyWrapper> KeyWrapper for DekCache<W> {
+    fn algorithm(&self) -> Algorithm {
+        self.inner.algorithm()
+    }
+
+    fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.inner.wrap(plaintext)
+    }
+
+    fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        {
+            let now = Instant::now();
+            let mut entries = self.entries.lock().unwrap();
+            match entries.get_mut(wrapped) {
+                Some(e) if e.expires_at > now => {
+                    e.last_used = now;
+                    return UnwrappedKey::from_slice(&e.dek);
+                }
+                Some(_) => {
+                    entries.remove(wrapped);
+                }
+                None => {}
+            }
+        }
+
+        let dek = self.inner.unwrap(wrapped)?;
+        self.insert(wrapped, dek.expose_secret());
+        Ok(dek)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockCall, MockKeyWrapper};
+    use std::thread;
+
+    fn unwrap_calls(cache: &DekCache<MockKeyWrapper>) -> usize {
+        cache
+            .inner()
+            .calls()
+            .iter()
+            .filter(|c| matches!(c, MockCall::Unwrap(_)))
+            .count()
+    }
+
+    #[test]
+    fn test_cache_hits_and_ttl() {
+        let cache = DekCache::new(
+            MockKeyWrapper::new(Algorithm::Kw),
+            Duration::from_millis(50),
+            8,
+        );
+        let wrapped = cache.wrap(b"dek-1").unwrap();
+        assert_eq!(b"dek-1", cache.unwrap(&wrapped).unwrap().expose_secret());
+        assert_eq!(b"dek-1", cache.unwrap(&wrapped).unwrap().expose_secret());
+        assert_eq!(1, unwrap_calls(&cache));
+
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(b"dek-1", cache.unwrap(&wrapped).unwrap().expose_secret());
+        assert_eq!(2, unwrap_calls(&cache));
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let cache = DekCache::new(
+            MockKeyWrapper::new(Algorithm::Kw),
+            Duration::from_secs(60),
+            2,
+        );
+        let a = cache.wrap(b"a").unwrap();
+        let b = cache.wrap(b"b").unwrap();
+        let c = cache.wrap(b"c").unwrap();
+        cache.unwrap(&a).unwrap();
+        cache.unwrap(&b).unwrap();
+        cache.unwrap(&a).unwrap();
+        cache.unwrap(&c).unwrap();
+        assert_eq!(2, cache.len());
+        assert_eq!(3, unwrap_calls(&cache));
+        cache.unwrap(&a).unwrap();
+        assert_eq!(3, unwrap_calls(&cache));
+        cache.unwrap(&b).unwrap();
+        assert_eq!(4, unwrap_calls(&cache));
+    }
+
+    #[test]
+    fn test_cache_skips_failures() {
+        let cache = DekCache::new(
+            MockKeyWrapper::new(Algorithm::Kw),
+            Duration::from_secs(60),
+            2,
+        );
+        cache.inner().fail_next_unwrap_with_integrity_error();
+        let wrapped = cache.wrap(b"a").unwrap();
+        assert!(cache.unwrap(&wrapped).is_err());
+        assert!(cache.is_empty());
+        assert!(cache.unwrap(&wrapped).is_ok());
+    }
+}
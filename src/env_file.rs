@@ -0,0 +1,255 @@
+use crate::{EnvSecret, KeyWrapper, ZeroizingString, ENV_TOKEN_PREFIX};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// `.env.wrapped` format
+//
+//     # kek-id: tenant-7
+//     # any other comment
+//     DATABASE_URL=kwp.4f1c...
+//     API_TOKEN=kwp.9a02...
+//
+// The first line names the KEK the values are wrapped under; every other
+// non-empty, non-comment line is NAME=<token> as produced by `EnvSecret`.
+
+const KEK_ID_HEADER: &str = "# kek-id: ";
+
+/// Parsed `.env.wrapped` file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WrappedEnvFile {
+    kek_id: String,
+    entries: Vec<(String, String)>,
+}
+
+fn check_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = match chars.next() {
+        Some(c) if c == '_' || c.is_ascii_alphabetic() => {
+            chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+        }
+        _ => false,
+    };
+    if !valid {
+        return Err(format!("Invalid variable name: {:?}", name));
+    }
+    Ok(())
+}
+
+impl WrappedEnvFile {
+    /// Empty file for values wrapped under the KEK `kek_id`
+    pub fn new(kek_id: &str) -> Result<WrappedEnvFile, String> {
+        if kek_id.is_empty() || kek_id.contains('\n') {
+            return Err(format!("Invalid kek id: {:?}", kek_id));
+        }
+        Ok(WrappedEnvFile {
+            kek_id: kek_id.to_string(),
+            entries: Vec::new(),
+        })
+    }
+
+    pub fn parse(contents: &str) -> Result<WrappedEnvFile, String> {
+        let mut lines = contents.lines();
+        let kek_id = lines
+            .next()
+            .and_then(|l| l.strip_prefix(KEK_ID_HEADER))
+            .map(str::trim)
+            .ok_or_else(|| format!("Missing header line \"{}<id>\"", KEK_ID_HEADER))?;
+        let mut file = WrappedEnvFile::new(kek_id)?;
+
+        for (lineno, line) in lines.enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let pos = line
+                .find('=')
+                .ok_or_else(|| format!("line {}: expected NAME=<token>", lineno + 2))?;
+            let (name, token) = (&line[..pos], &line[pos + 1..]);
+            check_name(name).map_err(|e| format!("line {}: {}", lineno + 2, e))?;
+            if !token.starts_with(ENV_TOKEN_PREFIX) {
+                return Err(format!(
+                    "line {}: {} is not a wrapped token",
+                    lineno + 2,
+                    name
+                ));
+            }
+            if file.token(name).is_some() {
+                return Err(format!("line {}: duplicate variable {}", lineno + 2, name));
+            }
+            file.entries.push((name.to_string(), token.to_string()));
+        }
+
+        Ok(file)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<WrappedEnvFile, String> {
+        let contents = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("{}: {}", path.as_ref().display(), e))?;
+        Self::parse(&contents)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        fs::write(path.as_ref(), self.to_string())
+            .map_err(|e| format!("{}: {}", path.as_ref().display(), e))
+    }
+
+    pub fn kek_id(&self) -> &str {
+        &self.kek_id
+    }
+
+    /// Variable names in file order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Wrapped token stored for `name`
+    pub fn token(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, token)| token.as_str())
+    }
+
+    /// Wrap `value` and store it under `name`, replacing any previous value
+    pub fn set(&mut self, wrapper: &dyn KeyWrapper, name: &str, value: &str) -> Result<(), String> {
+        check_name(name)?;
+        let token = EnvSecret::encode(wrapper, value)?;
+        match self.entries.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = token,
+            None => self.entries.push((name.to_string(), token)),
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|(n, _)| n != name);
+        self.entries.len() != len
+    }
+
+    /// Unwrap the value stored under `name`
+    pub fn get(
+        &self,
+        wrapper: &dyn KeyWrapper,
+        name: &str,
+    ) -> Result<Option<ZeroizingString>, String> {
+        match self.token(name) {
+            Some(token) => EnvSecret::decode(wrapper, token).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Unwrap every value, failing on the first one that doesn't unwrap
+    pub fn decrypt_all(
+        &self,
+        wrapper: &dyn KeyWrapper,
+    ) -> Result<Vec<(String, ZeroizingString)>, String> {
+        self.entries
+            .iter()
+            .map(|(name, token)| {
+                EnvSecret::decode(wrapper, token)
+                    .map(|value| (name.clone(), value))
+                    .map_err(|e| format!("{}: {}", name, e))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for WrappedEnvFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}{}", KEK_ID_HEADER, self.kek_id)?;
+        for (name, token) in &self.entries {
+            writeln!(f, "{}={}", name, token)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AesKeyWrapper, Algorithm};
+
+    // path under the temp dir unique to this process and test, removed
+    // when dropped even if the test fails
+    struct TempPath(std::path::PathBuf);
+
+    impl TempPath {
+        fn new(test: &str) -> TempPath {
+            TempPath(std::env::temp_dir().join(format!(
+                "aes-keywrap-rs-{}-{}.env.wrapped",
+                std::process::id(),
+                test
+            )))
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn wrapper() -> AesKeyWrapper {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        AesKeyWrapper::new(&kek, Algorithm::Kwp).unwrap()
+    }
+
+    #[test]
+    fn test_env_file_round_trip() {
+        let wrapper = wrapper();
+        let mut file = WrappedEnvFile::new("tenant-7").unwrap();
+        file.set(&wrapper, "DATABASE_URL", "postgres://db/app")
+            .unwrap();
+        file.set(&wrapper, "API_TOKEN", "first").unwrap();
+        file.set(&wrapper, "API_TOKEN", "second").unwrap();
+
+        let text = file.to_string();
+        assert!(text.starts_with("# kek-id: tenant-7\n"));
+        assert!(!text.contains("postgres"));
+
+        let parsed = WrappedEnvFile::parse(&text).unwrap();
+        assert_eq!(file, parsed);
+        assert_eq!(
+            vec!["DATABASE_URL", "API_TOKEN"],
+            parsed.names().collect::<Vec<_>>()
+        );
+        let values = parsed.decrypt_all(&wrapper).unwrap();
+        assert_eq!("postgres://db/app", values[0].1.expose_secret());
+        assert_eq!("second", values[1].1.expose_secret());
+        assert!(parsed.get(&wrapper, "MISSING").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_env_file_save_and_load() {
+        let wrapper = wrapper();
+        let mut file = WrappedEnvFile::new("tenant-7").unwrap();
+        file.set(&wrapper, "SECRET", "value").unwrap();
+        let path = TempPath::new("save_and_load");
+        file.save(&path.0).unwrap();
+        let loaded = WrappedEnvFile::load(&path.0).unwrap();
+        assert_eq!(
+            "value",
+            loaded
+                .get(&wrapper, "SECRET")
+                .unwrap()
+                .unwrap()
+                .expose_secret()
+        );
+        assert!(file.remove("SECRET"));
+        assert!(!file.remove("SECRET"));
+    }
+
+    #[test]
+    fn test_env_file_parse_errors() {
+        assert!(WrappedEnvFile::parse("A=kwp.00\n").is_err());
+        assert!(WrappedEnvFile::parse("# kek-id: k\nA=plain\n").is_err());
+        assert!(WrappedEnvFile::parse("# kek-id: k\n1A=kwp.00\n").is_err());
+        assert!(WrappedEnvFile::parse("# kek-id: k\nA=kwp.00\nA=kwp.01\n").is_err());
+        assert!(WrappedEnvFile::parse("# kek-id: k\njunk\n").is_err());
+        let file = WrappedEnvFile::parse("# kek-id: k\n\n# comment\nA=kwp.00\n").unwrap();
+        assert_eq!("k", file.kek_id());
+        assert_eq!(Some("kwp.00"), file.token("A"));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Algorithm, KeyWrapper};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Prefix of wrapped environment tokens: `kwp.` + hex(RFC5649 wrapped value)
+pub const ENV_TOKEN_PREFIX: &str = "kwp.";
+
+/// String that overwrites its bytes when dropped and never prints them
+pub struct ZeroizingString(String);
+
+impl ZeroizingString {
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ZeroizingString {
+    fn from(value: String) -> Self {
+        ZeroizingString(value)
+    }
+}
+
+impl Drop for ZeroizingString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for ZeroizingString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ZeroizingString(..redacted..)")
+    }
+}
+
+fn check_algorithm(wrapper: &dyn KeyWrapper) -> Result<(), String> {
+    if wrapper.algorithm() != Algorithm::Kwp {
+        return Err(String::from("Environment secrets need a Kwp key wrapper"));
+    }
+    Ok(())
+}
+
+/// Helpers for secrets kept as wrapped tokens in environment variables
+pub struct EnvSecret;
+
+impl EnvSecret {
+    /// Wrap `value` into a URL-safe token suitable for an environment variable
+    pub fn encode(wrapper: &dyn KeyWrapper, value: &str) -> Result<String, String> {
+        check_algorithm(wrapper)?;
+        let wrapped = wrapper.wrap(value.as_bytes())?;
+        Ok(format!("{}{}", ENV_TOKEN_PREFIX, hex::encode(wrapped)))
+    }
+
+    /// Unwrap a token produced by `encode`
+    pub fn decode(wrapper: &dyn KeyWrapper, token: &str) -> Result<ZeroizingString, String> {
+        check_algorithm(wrapper)?;
+        let encoded = token
+            .strip_prefix(ENV_TOKEN_PREFIX)
+            .ok_or_else(|| String::from("Not a wrapped environment token"))?;
+        let wrapped = hex::decode(encoded).map_err(|e| format!("Invalid token encoding: {}", e))?;
+        let value = wrapper.unwrap(&wrapped)?;
+        String::from_utf8(value.expose_secret().to_vec())
+            .map(ZeroizingString)
+            .map_err(|e| {
+                e.into_bytes().zeroize();
+                String::from("Unwrapped value is not UTF-8")
+            })
+    }
+
+    /// Read the environment variable `name` and unwrap its token
+    pub fn load(wrapper: &dyn KeyWrapper, name: &str) -> Result<ZeroizingString, String> {
+        let token = std::env::var(name).map_err(|e| format!("{}: {}", name, e))?;
+        Self::decode(wrapper, &token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AesKeyWrapper;
+
+    #[test]
+    fn test_env_secret_round_trip() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapper = AesKeyWrapper::new(&kek, Algorithm::Kwp).unwrap();
+        let token = EnvSecret::encode(&wrapper, "postgres://user:hunter2@db/app").unwrap();
+        assert!(token.starts_with(ENV_TOKEN_PREFIX));
+        assert!(!token.contains("hunter2"));
+
+        std::env::set_var("AES_KEYWRAP_RS_TEST_SECRET", &token);
+        let secret = EnvSecret::load(&wrapper, "AES_KEYWRAP_RS_TEST_SECRET").unwrap();
+        assert_eq!("postgres://user:hunter2@db/app", secret.expose_secret());
+        assert_eq!("ZeroizingString(..redacted..)", format!("{:?}", secret));
+    }
+
+    #[test]
+    fn test_env_secret_errors() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapper = AesKeyWrapper::new(&kek, Algorithm::Kwp).unwrap();
+        assert!(EnvSecret::decode(&wrapper, "plaintext").is_err());
+        assert!(EnvSecret::decode(&wrapper, "kwp.zz").is_err());
+        let mut token = EnvSecret::encode(&wrapper, "secret").unwrap();
+        let flipped = if &token[4..5] == "0" { "1" } else { "0" };
+        token.replace_range(4..5, flipped);
+        assert!(EnvSecret::decode(&wrapper, &token).is_err());
+        assert!(EnvSecret::load(&wrapper, "AES_KEYWRAP_RS_UNSET_VARIABLE").is_err());
+
+        let kw = AesKeyWrapper::new(&kek, Algorithm::Kw).unwrap();
+        assert!(EnvSecret::encode(&kw, "secret").is_err());
+    }
+}
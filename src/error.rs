@@ -0,0 +1,121 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// Why a fallible (`try_*`) wrap or unwrap failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "mobile", derive(uniffi::Error), uniffi(flat_error))]
+pub enum KeyWrapError {
+    /// KEK is not 16, 24 or 32 bytes long
+    InvalidKekLength(usize),
+    /// Plaintext length not allowed by the algorithm
+    InvalidPlaintextLength(usize),
+    /// Wrapped input length not allowed by the algorithm
+    InvalidCiphertextLength(usize),
+    /// Recovered ICV/AIV doesn't match
+    IntegrityCheckFailed,
+    /// RFC5649 message length indicator outside 8*(n-1) < MLI <= 8*n
+    InvalidMli(u32),
+    /// RFC5649 padding octets are not zero
+    InvalidPadding,
+    /// Input too large for the algorithm's length encoding
+    LengthOverflow(usize),
+    /// Unwrap rejected the input; the opaque unwrap functions report every
+    /// length, integrity, MLI and padding failure this way
+    UnwrapFailed,
+    /// Key buffer could not be locked in memory (`mlock` feature)
+    MemoryLockFailed,
+    /// Caller-provided buffer is shorter than the given required length
+    BufferTooSmall(usize),
+    /// The power-up known-answer tests failed (`self-test` feature); every
+    /// wrap and unwrap is refused for the life of the process
+    SelfTestFailed,
+    /// A caller-supplied block cipher (`BlockCipherKw`) reported an error
+    BlockCipherFailed,
+    /// A remote `KeyWrapper` timed out, was throttled or is behind an open
+    /// circuit breaker; the call may succeed if retried later
+    ProviderUnavailable,
+}
+
+impl fmt::Display for KeyWrapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyWrapError::InvalidKekLength(len) => {
+                write!(
+                    f,
+                    "kek is not supported: {} bytes (expected 16, 24 or 32)",
+                    len
+                )
+            }
+            KeyWrapError::InvalidPlaintextLength(len) => {
+                write!(f, "Invalid plaintext length: {}", len)
+            }
+            KeyWrapError::InvalidCiphertextLength(len) => {
+                write!(f, "Invalid wrapped length: {}", len)
+            }
+            KeyWrapError::IntegrityCheckFailed => write!(f, "Integrity check failed"),
+            KeyWrapError::InvalidMli(mli) => {
+                write!(f, "Invalid message length indicator: {}", mli)
+            }
+            KeyWrapError::InvalidPadding => write!(f, "Invalid padding"),
+            KeyWrapError::LengthOverflow(len) => write!(f, "Input too large: {} bytes", len),
+            KeyWrapError::UnwrapFailed => write!(f, "Unwrap failed"),
+            KeyWrapError::MemoryLockFailed => write!(f, "Failed to lock key memory"),
+            KeyWrapError::BufferTooSmall(len) => {
+                write!(f, "Buffer too small: {} bytes needed", len)
+            }
+            KeyWrapError::SelfTestFailed => write!(f, "Power-up self-test failed"),
+            KeyWrapError::BlockCipherFailed => write!(f, "Block cipher operation failed"),
+            KeyWrapError::ProviderUnavailable => write!(f, "Key wrap provider unavailable"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyWrapError {}
+
+impl From<KeyWrapError> for String {
+    fn from(e: KeyWrapError) -> String {
+        e.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::try_unwrap_key;
+    #[cfg(feature = "std")]
+    use std::error::Error;
+
+    #[cfg(feature = "std")]
+    fn unwrap_boxed(kek: &[u8], wrapped: &[u8]) -> Result<usize, Box<dyn Error>> {
+        Ok(try_unwrap_key(kek, wrapped)?.len())
+    }
+
+    fn unwrap_string(kek: &[u8], wrapped: &[u8]) -> Result<usize, String> {
+        Ok(try_unwrap_key(kek, wrapped)?.len())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_boxed_error() {
+        let err = unwrap_boxed(&[0u8; 16], &[0u8; 24]).unwrap_err();
+        assert_eq!(
+            Some(&KeyWrapError::IntegrityCheckFailed),
+            err.downcast_ref::<KeyWrapError>()
+        );
+    }
+
+    #[test]
+    fn test_error_conversions() {
+        let kek = [0u8; 16];
+        assert_eq!(
+            Err(String::from("Invalid wrapped length: 20")),
+            unwrap_string(&kek, &[0u8; 20])
+        );
+        assert_eq!(
+            "kek is not supported: 8 bytes (expected 16, 24 or 32)",
+            KeyWrapError::InvalidKekLength(8).to_string()
+        );
+    }
+}
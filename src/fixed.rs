@@ -0,0 +1,184 @@
+use crate::{
+    check_kek_len, unwrap_key_in_place, unwrap_key_with_pad_in_place, wrap_key_in_place,
+    wrap_key_with_pad_in_place, wrapped_len_with_pad, KeyWrapError,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+// Fixed-size API
+//
+// Wrap and unwrap `[u8; N]` keys with nothing but stack storage, for
+// targets without a heap. Stable Rust can't write the output type as
+// `[u8; N + 8]`, so the wrapped length is a second const parameter `M`,
+// usually inferred from the binding; a mismatched pair of lengths fails
+// to compile rather than returning an error.
+
+struct Lengths<const N: usize, const M: usize>;
+
+impl<const N: usize, const M: usize> Lengths<N, M> {
+    const KW: () = assert!(
+        N >= 16 && N.is_multiple_of(8) && M == N + 8,
+        "RFC3394 needs N a multiple of 8, at least 16, and M == N + 8"
+    );
+    const KWP: () = assert!(
+        N > 0 && M == wrapped_len_with_pad(N),
+        "RFC5649 needs N > 0 and M == wrapped_len_with_pad(N)"
+    );
+}
+
+// run `wrap` over the plaintext copied behind the ICV semiblock, clearing
+// the output if it fails after the copy
+fn wrap_array<const N: usize, const M: usize, F>(
+    kek: &[u8],
+    plaintext: &[u8; N],
+    wrap: F,
+) -> Result<[u8; M], KeyWrapError>
+where
+    F: FnOnce(&mut [u8; M]) -> Result<usize, KeyWrapError>,
+{
+    check_kek_len(kek)?;
+
+    let mut out = [0u8; M];
+    out[8..8 + N].copy_from_slice(plaintext);
+    if let Err(e) = wrap(&mut out) {
+        out.zeroize();
+        return Err(e);
+    }
+    Ok(out)
+}
+
+/// Wrap key (RFC3394) into a `[u8; M]`, `M == N + 8`
+pub fn wrap_fixed<const N: usize, const M: usize>(
+    kek: &[u8],
+    plaintext: &[u8; N],
+) -> Result<[u8; M], KeyWrapError> {
+    #[allow(clippy::let_unit_value)]
+    let () = Lengths::<N, M>::KW;
+    wrap_array(kek, plaintext, |buf| wrap_key_in_place(kek, buf))
+}
+
+/// Unwrap key (RFC3394) from a `[u8; M]`, `M == N + 8`
+pub fn unwrap_fixed<const N: usize, const M: usize>(
+    kek: &[u8],
+    wrapped: &[u8; M],
+) -> Result<Zeroizing<[u8; N]>, KeyWrapError> {
+    #[allow(clippy::let_unit_value)]
+    let () = Lengths::<N, M>::KW;
+
+    let mut buf = Zeroizing::new(*wrapped);
+    let key = unwrap_key_in_place(kek, &mut buf[..])?;
+    let mut out = Zeroizing::new([0u8; N]);
+    out.copy_from_slice(key);
+    Ok(out)
+}
+
+/// Wrap key with pad (RFC5649) into a `[u8; M]`,
+/// `M == wrapped_len_with_pad(N)`
+pub fn wrap_fixed_with_pad<const N: usize, const M: usize>(
+    kek: &[u8],
+    plaintext: &[u8; N],
+) -> Result<[u8; M], KeyWrapError> {
+    #[allow(clippy::let_unit_value)]
+    let () = Lengths::<N, M>::KWP;
+    wrap_array(kek, plaintext, |buf| {
+        wrap_key_with_pad_in_place(kek, buf, N)
+    })
+}
+
+/// Unwrap key with pad (RFC5649) from a `[u8; M]`,
+/// `M == wrapped_len_with_pad(N)`
+///
+/// The message length indicator must be exactly `N`; any other length
+/// that is valid for `M` is reported as `KeyWrapError::InvalidMli`.
+pub fn unwrap_fixed_with_pad<const N: usize, const M: usize>(
+    kek: &[u8],
+    wrapped: &[u8; M],
+) -> Result<Zeroizing<[u8; N]>, KeyWrapError> {
+    #[allow(clippy::let_unit_value)]
+    let () = Lengths::<N, M>::KWP;
+
+    let mut buf = Zeroizing::new(*wrapped);
+    let key = unwrap_key_with_pad_in_place(kek, &mut buf[..])?;
+    if key.len() != N {
+        return Err(KeyWrapError::InvalidMli(key.len() as u32));
+    }
+    let mut out = Zeroizing::new([0u8; N]);
+    out.copy_from_slice(key);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryInto;
+
+    fn array<const N: usize>(s: &str) -> [u8; N] {
+        hex::decode(s).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_fixed_kw() {
+        // RFC3394 4.1 and 4.6
+        let kek: [u8; 16] = array("000102030405060708090A0B0C0D0E0F");
+        let plain: [u8; 16] = array("00112233445566778899AABBCCDDEEFF");
+        let wrapped: [u8; 24] = wrap_fixed(&kek, &plain).unwrap();
+        assert_eq!(
+            array::<24>("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5"),
+            wrapped
+        );
+        assert_eq!(plain, *unwrap_fixed::<16, 24>(&kek, &wrapped).unwrap());
+
+        let kek: [u8; 32] =
+            array("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F");
+        let plain: [u8; 32] =
+            array("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F");
+        let wrapped: [u8; 40] = wrap_fixed(&kek, &plain).unwrap();
+        assert_eq!(
+            array::<40>(
+                "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21"
+            ),
+            wrapped
+        );
+        assert_eq!(plain, *unwrap_fixed::<32, 40>(&kek, &wrapped).unwrap());
+
+        let mut tampered = wrapped;
+        tampered[0] ^= 1;
+        assert_eq!(
+            Err(KeyWrapError::IntegrityCheckFailed),
+            unwrap_fixed::<32, 40>(&kek, &tampered).map(|k| *k)
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidKekLength(8)),
+            wrap_fixed::<32, 40>(&kek[..8], &plain)
+        );
+    }
+
+    #[test]
+    fn test_fixed_kwp() {
+        // RFC5649 section 6
+        let kek: [u8; 24] = array("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8");
+        let plain: [u8; 20] = array("C37B7E6492584340BED12207808941155068F738");
+        let wrapped: [u8; 32] = wrap_fixed_with_pad(&kek, &plain).unwrap();
+        assert_eq!(
+            array::<32>("138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A"),
+            wrapped
+        );
+        assert_eq!(
+            plain,
+            *unwrap_fixed_with_pad::<20, 32>(&kek, &wrapped).unwrap()
+        );
+
+        let plain: [u8; 7] = array("466F7250617369");
+        let wrapped: [u8; 16] = wrap_fixed_with_pad(&kek, &plain).unwrap();
+        assert_eq!(array::<16>("AFBEB0F07DFBF5419200F2CCB50BB24F"), wrapped);
+        assert_eq!(
+            plain,
+            *unwrap_fixed_with_pad::<7, 16>(&kek, &wrapped).unwrap()
+        );
+
+        // valid for a 16-octet wrap, but not a 5-octet key
+        assert_eq!(
+            Err(KeyWrapError::InvalidMli(7)),
+            unwrap_fixed_with_pad::<5, 16>(&kek, &wrapped).map(|k| *k)
+        );
+    }
+}
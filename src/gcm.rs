@@ -0,0 +1,156 @@
+use crate::locked::locked_copy;
+use crate::{self_test, KeyWrapError, UnwrappedKey};
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::{AeadInPlace, KeyInit, Nonce, Tag};
+use aes_gcm::aes::Aes192;
+use aes_gcm::{Aes128Gcm, Aes256Gcm, AesGcm};
+use alloc::vec::Vec;
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+// AES-GCM wrapping with a random nonce (`gcm` feature)
+//
+// For interop with systems that wrap keys with AES-GCM rather than KW, e.g.
+// Android Keystore inner blobs and most cloud KMS products. The wrapped form
+// is nonce (12) | ciphertext | tag (16). GCM breaks down if a nonce repeats
+// under one KEK, so nonces are random (from the OS, or the caller's RNG) and
+// a KEK should seal well under 2^32 keys.
+
+type Aes192Gcm = AesGcm<Aes192, U12>;
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+fn seal<C: AeadInPlace + KeyInit>(
+    kek: &[u8],
+    nonce: &[u8; NONCE_LEN],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, KeyWrapError> {
+    let cipher = C::new_from_slice(kek).map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))?;
+    let mut buf: Vec<u8> = Vec::with_capacity(NONCE_LEN + plaintext.len() + TAG_LEN);
+    buf.extend_from_slice(nonce);
+    buf.extend_from_slice(plaintext);
+    let tag = cipher
+        .encrypt_in_place_detached(Nonce::<C>::from_slice(nonce), aad, &mut buf[NONCE_LEN..])
+        .map_err(|_| KeyWrapError::LengthOverflow(plaintext.len()))?;
+    buf.extend_from_slice(&tag);
+    Ok(buf)
+}
+
+fn open<C: AeadInPlace + KeyInit>(
+    kek: &[u8],
+    wrapped: &[u8],
+    aad: &[u8],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    let cipher = C::new_from_slice(kek).map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))?;
+    let (nonce, rest) = wrapped.split_at(NONCE_LEN);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+    let mut buf = locked_copy(ciphertext)?;
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::<C>::from_slice(nonce),
+            aad,
+            &mut buf,
+            Tag::<C>::from_slice(tag),
+        )
+        .map_err(|_| KeyWrapError::IntegrityCheckFailed)?;
+    UnwrappedKey::from_slice(&buf)
+}
+
+/// Wrap key with AES-GCM under a fresh random nonce from the OS,
+/// authenticating `aad` alongside it
+pub fn try_aes_gcm_wrap_key(
+    kek: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, KeyWrapError> {
+    try_aes_gcm_wrap_key_with_rng(kek, plaintext, aad, &mut OsRng)
+}
+
+/// Wrap key with AES-GCM, drawing the nonce from `rng`
+pub fn try_aes_gcm_wrap_key_with_rng<R: CryptoRng + RngCore>(
+    kek: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, KeyWrapError> {
+    if plaintext.is_empty() {
+        return Err(KeyWrapError::InvalidPlaintextLength(0));
+    }
+    self_test::power_up()?;
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    match kek.len() {
+        16 => seal::<Aes128Gcm>(kek, &nonce, plaintext, aad),
+        24 => seal::<Aes192Gcm>(kek, &nonce, plaintext, aad),
+        32 => seal::<Aes256Gcm>(kek, &nonce, plaintext, aad),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+/// Unwrap a nonce | ciphertext | tag blob and check the tag over it and
+/// `aad`
+pub fn try_aes_gcm_unwrap_key(
+    kek: &[u8],
+    wrapped: &[u8],
+    aad: &[u8],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    if wrapped.len() <= NONCE_LEN + TAG_LEN {
+        return Err(KeyWrapError::InvalidCiphertextLength(wrapped.len()));
+    }
+    self_test::power_up()?;
+    match kek.len() {
+        16 => open::<Aes128Gcm>(kek, wrapped, aad),
+        24 => open::<Aes192Gcm>(kek, wrapped, aad),
+        32 => open::<Aes256Gcm>(kek, wrapped, aad),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::CountingRng;
+
+    #[test]
+    fn test_aes_gcm() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        for (kek_len, aad, cipher) in [
+            (16, &b""[..], "000102030405060708090A0B937D85FD224E9123C34BCB31FA7E9EF724F70EBD2B2F5579726DC56E4040A12D"),
+            (24, &b"aad"[..], "000102030405060708090A0BE6E800A8DDECAF7958AA76393374328F5881083A950F03E47939F6FCB005610E"),
+            (32, &b"aad"[..], "000102030405060708090A0B4713F42881B0A46C05D83D307D349692CB82BBC60D327C2BFDA6DB86D54FF80F"),
+        ]
+        .iter()
+        {
+            let kek = &kek[..*kek_len];
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(
+                cipher,
+                try_aes_gcm_wrap_key_with_rng(kek, &plain, aad, &mut CountingRng(0)).unwrap()
+            );
+            assert_eq!(
+                &plain[..],
+                try_aes_gcm_unwrap_key(kek, &cipher, aad)
+                    .unwrap()
+                    .expose_secret()
+            );
+            assert_eq!(
+                KeyWrapError::IntegrityCheckFailed,
+                try_aes_gcm_unwrap_key(kek, &cipher, b"other").unwrap_err()
+            );
+        }
+
+        // fresh nonce every time
+        let a = try_aes_gcm_wrap_key(&kek, &plain, b"").unwrap();
+        let b = try_aes_gcm_wrap_key(&kek, &plain, b"").unwrap();
+        assert_ne!(a, b);
+        assert_eq!(
+            &plain[..],
+            try_aes_gcm_unwrap_key(&kek, &b, b"")
+                .unwrap()
+                .expose_secret()
+        );
+    }
+}
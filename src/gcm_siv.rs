@@ -0,0 +1,128 @@
+use crate::locked::locked_copy;
+use crate::{self_test, KeyWrapError, UnwrappedKey};
+use aes_gcm_siv::aead::{AeadInPlace, KeyInit, Nonce, Tag};
+use aes_gcm_siv::{Aes128GcmSiv, Aes256GcmSiv};
+use alloc::vec::Vec;
+
+// AES-GCM-SIV wrapping (RFC 8452, `gcm-siv` feature)
+//
+// Nonce-misuse-resistant AEAD as an alternative to KW/KWP: the synthetic IV
+// is derived from the key material and AAD, so like KW the output is
+// deterministic and only reveals whether two wraps had the same input. The
+// nonce is fixed at zero and not stored; the wrapped form is
+// ciphertext | 16-byte tag. POLYVAL and AES-CTR pipeline well on AES-NI /
+// PMULL, where this is several times faster than KW's 6n chained blocks.
+
+const TAG_LEN: usize = 16;
+
+fn seal<C: AeadInPlace + KeyInit>(
+    kek: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, KeyWrapError> {
+    let cipher = C::new_from_slice(kek).map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))?;
+    let mut buf: Vec<u8> = Vec::with_capacity(plaintext.len() + TAG_LEN);
+    buf.extend_from_slice(plaintext);
+    let tag = cipher
+        .encrypt_in_place_detached(&Nonce::<C>::default(), aad, &mut buf)
+        .map_err(|_| KeyWrapError::LengthOverflow(plaintext.len()))?;
+    buf.extend_from_slice(&tag);
+    Ok(buf)
+}
+
+fn open<C: AeadInPlace + KeyInit>(
+    kek: &[u8],
+    wrapped: &[u8],
+    aad: &[u8],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    let cipher = C::new_from_slice(kek).map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))?;
+    let (ciphertext, tag) = wrapped.split_at(wrapped.len() - TAG_LEN);
+    let mut buf = locked_copy(ciphertext)?;
+    cipher
+        .decrypt_in_place_detached(
+            &Nonce::<C>::default(),
+            aad,
+            &mut buf,
+            Tag::<C>::from_slice(tag),
+        )
+        .map_err(|_| KeyWrapError::IntegrityCheckFailed)?;
+    UnwrappedKey::from_slice(&buf)
+}
+
+/// Wrap key with AES-GCM-SIV (RFC 8452) under a 128 or 256-bit KEK,
+/// authenticating `aad` alongside it
+pub fn try_aes_gcm_siv_wrap_key(
+    kek: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, KeyWrapError> {
+    if plaintext.is_empty() {
+        return Err(KeyWrapError::InvalidPlaintextLength(0));
+    }
+    self_test::power_up()?;
+    match kek.len() {
+        16 => seal::<Aes128GcmSiv>(kek, plaintext, aad),
+        32 => seal::<Aes256GcmSiv>(kek, plaintext, aad),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+/// Unwrap key and check the AES-GCM-SIV tag over it and `aad`
+pub fn try_aes_gcm_siv_unwrap_key(
+    kek: &[u8],
+    wrapped: &[u8],
+    aad: &[u8],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    if wrapped.len() <= TAG_LEN {
+        return Err(KeyWrapError::InvalidCiphertextLength(wrapped.len()));
+    }
+    self_test::power_up()?;
+    match kek.len() {
+        16 => open::<Aes128GcmSiv>(kek, wrapped, aad),
+        32 => open::<Aes256GcmSiv>(kek, wrapped, aad),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aes_gcm_siv() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        for (kek, aad, cipher) in [
+            (
+                &kek[..16],
+                &b""[..],
+                "918D8D591B149EC9269CDC4A17ED950839384C0E4A92D5D2D06546633FB0133A",
+            ),
+            (
+                &kek[..],
+                &b"aad"[..],
+                "5B704EA73E214898267444D35BA62B1F3B743C9C319FCE281AB05D5952542B2A",
+            ),
+        ]
+        .iter()
+        {
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(cipher, try_aes_gcm_siv_wrap_key(kek, &plain, aad).unwrap());
+            assert_eq!(
+                &plain[..],
+                try_aes_gcm_siv_unwrap_key(kek, &cipher, aad)
+                    .unwrap()
+                    .expose_secret()
+            );
+            assert_eq!(
+                KeyWrapError::IntegrityCheckFailed,
+                try_aes_gcm_siv_unwrap_key(kek, &cipher, b"other").unwrap_err()
+            );
+        }
+        assert_eq!(
+            KeyWrapError::InvalidKekLength(24),
+            try_aes_gcm_siv_wrap_key(&kek[..24], &plain, b"").unwrap_err()
+        );
+    }
+}
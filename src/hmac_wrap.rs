@@ -0,0 +1,124 @@
+use crate::cms_tdes;
+use crate::{try_unwrap_key, try_wrap_key, KeyWrapError, UnwrappedKey};
+use alloc::vec::Vec;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+// RFC 3537 HMAC key wrapping for CMS (`hmac-wrap` feature)
+//
+// The HMAC key is prefixed with its length octet and padded with random
+// octets to a multiple of 8 (LKEYPAD), then wrapped with either
+// * id-alg-HMACwithAESwrap: RFC3394 AES key wrap
+// * id-alg-HMACwith3DESwrap: the RFC 3217 CMS Triple-DES key wrap (SHA-1
+//   checksum, two CBC passes with a random IV and a byte reversal)
+// The random octets come from the caller's RNG, so this works without std.
+
+/// id-alg-HMACwith3DESwrap (RFC 3537 Section 2)
+pub const ID_ALG_HMAC_WITH_3DES_WRAP: &str = "1.2.840.113549.1.9.16.3.11";
+/// id-alg-HMACwithAESwrap (RFC 3537 Section 3)
+pub const ID_ALG_HMAC_WITH_AES_WRAP: &str = "1.2.840.113549.1.9.16.3.12";
+
+// LENGTH | KEY | PAD
+fn lkeypad<R: CryptoRng + RngCore>(
+    hmac_key: &[u8],
+    rng: &mut R,
+) -> Result<Zeroizing<Vec<u8>>, KeyWrapError> {
+    let len = hmac_key.len();
+    if len > u8::MAX as usize {
+        return Err(KeyWrapError::InvalidPlaintextLength(len));
+    }
+    let padded_len = (len + 1).div_ceil(8) * 8;
+    let mut buf = Zeroizing::new(Vec::with_capacity(padded_len));
+    buf.push(len as u8);
+    buf.extend_from_slice(hmac_key);
+    buf.resize(padded_len, 0);
+    rng.fill_bytes(&mut buf[len + 1..]);
+    Ok(buf)
+}
+
+// KEY out of LENGTH | KEY | PAD, with at most 7 pad octets
+fn strip_lkeypad(buf: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    let len = buf[0] as usize;
+    if len + 1 > buf.len() || buf.len() - (len + 1) > 7 {
+        return Err(KeyWrapError::InvalidPadding);
+    }
+    UnwrappedKey::from_slice(&buf[1..len + 1])
+}
+
+/// Wrap an HMAC key with an AES KEK (id-alg-HMACwithAESwrap); keys of at
+/// least 8 octets, as RFC3394 needs two semiblocks
+pub fn hmac_wrap_key_aes<R: CryptoRng + RngCore>(
+    kek: &[u8],
+    hmac_key: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, KeyWrapError> {
+    if hmac_key.len() < 8 {
+        return Err(KeyWrapError::InvalidPlaintextLength(hmac_key.len()));
+    }
+    try_wrap_key(kek, &lkeypad(hmac_key, rng)?)
+}
+
+/// Unwrap an HMAC key wrapped with id-alg-HMACwithAESwrap
+pub fn hmac_unwrap_key_aes(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    strip_lkeypad(try_unwrap_key(kek, wrapped)?.expose_secret())
+}
+
+/// Wrap an HMAC key with a 24-byte three-key TDEA KEK
+/// (id-alg-HMACwith3DESwrap)
+pub fn hmac_wrap_key_tdes<R: CryptoRng + RngCore>(
+    kek: &[u8],
+    hmac_key: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, KeyWrapError> {
+    cms_tdes::wrap(kek, &lkeypad(hmac_key, rng)?, rng)
+}
+
+/// Unwrap an HMAC key wrapped with id-alg-HMACwith3DESwrap
+pub fn hmac_unwrap_key_tdes(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    let buf = cms_tdes::unwrap(kek, wrapped)?;
+    strip_lkeypad(&buf[8..buf.len() - 8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::CountingRng;
+
+    #[test]
+    fn test_hmac_wrap() {
+        let key = hex::decode("00112233445566778899AABBCCDDEEFF0011223344").unwrap();
+
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapped = hmac_wrap_key_aes(&kek, &key, &mut CountingRng(0)).unwrap();
+        assert_eq!(
+            hex::decode("B7B89EE3C9524E010C79D50FB49D5D251B1728B54E901BB582BB6DC7B3642207")
+                .unwrap(),
+            wrapped
+        );
+        assert_eq!(
+            &key[..],
+            hmac_unwrap_key_aes(&kek, &wrapped).unwrap().expose_secret()
+        );
+
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F1011121314151617").unwrap();
+        let mut wrapped = hmac_wrap_key_tdes(&kek, &key, &mut CountingRng(0)).unwrap();
+        assert_eq!(
+            hex::decode(
+                "8164951E35341284C4B40460A7E31D001D0CF6F2A2737572DE5C50DA4BF3B813D522ADC1DE94448B"
+            )
+            .unwrap(),
+            wrapped
+        );
+        assert_eq!(
+            &key[..],
+            hmac_unwrap_key_tdes(&kek, &wrapped)
+                .unwrap()
+                .expose_secret()
+        );
+        wrapped[20] ^= 1;
+        assert_eq!(
+            KeyWrapError::IntegrityCheckFailed,
+            hmac_unwrap_key_tdes(&kek, &wrapped).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, KeyWrapError,
+    UnwrappedKey,
+};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use zeroize::Zeroize;
+
+// Key-encryption keys whose length is checked once, at construction, so
+// wrapping and unwrapping with them can't fail on the KEK itself. Each copy
+// is zeroized when it is dropped.
+
+macro_rules! impl_kek {
+    ($name: ident, $len: expr, $doc: expr) => {
+        #[doc = $doc]
+        #[derive(Clone)]
+        pub struct $name([u8; $len]);
+
+        impl From<[u8; $len]> for $name {
+            fn from(kek: [u8; $len]) -> Self {
+                $name(kek)
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = KeyWrapError;
+
+            fn try_from(kek: &[u8]) -> Result<Self, KeyWrapError> {
+                <[u8; $len]>::try_from(kek)
+                    .map($name)
+                    .map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), "(..redacted..)"))
+            }
+        }
+
+        impl $name {
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.0
+            }
+
+            /// Wrap key with the IV defined in RFC3394
+            pub fn wrap_key(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+                try_wrap_key(&self.0, plaintext)
+            }
+
+            /// Unwrap key and Check IV in RFC3394
+            pub fn unwrap_key(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+                try_unwrap_key(&self.0, wrapped)
+            }
+
+            /// Wrap key with pad using padding algorithm (RFC5649)
+            pub fn wrap_key_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+                try_wrap_key_with_pad(&self.0, plaintext)
+            }
+
+            /// Unwrap key with pad using padding algorithm (RFC5649)
+            pub fn unwrap_key_with_pad(
+                &self,
+                wrapped: &[u8],
+            ) -> Result<UnwrappedKey, KeyWrapError> {
+                try_unwrap_key_with_pad(&self.0, wrapped)
+            }
+
+            /// Unwrap a batch of keys (RFC3394), reporting each item's
+            /// outcome separately so one corrupted blob doesn't stop the
+            /// others from loading
+            pub fn unwrap_many<T: AsRef<[u8]>>(
+                &self,
+                wrapped: &[T],
+            ) -> Vec<Result<UnwrappedKey, KeyWrapError>> {
+                wrapped
+                    .iter()
+                    .map(|w| self.unwrap_key(w.as_ref()))
+                    .collect()
+            }
+        }
+    };
+}
+
+impl_kek!(Kek128, 16, "128-bit key-encryption key");
+impl_kek!(Kek192, 24, "192-bit key-encryption key");
+impl_kek!(Kek256, 32, "256-bit key-encryption key");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kek_construction() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        assert!(Kek128::try_from(kek.as_slice()).is_ok());
+        assert_eq!(
+            Some(KeyWrapError::InvalidKekLength(16)),
+            Kek256::try_from(kek.as_slice()).err()
+        );
+        assert_eq!(
+            Some(KeyWrapError::InvalidKekLength(15)),
+            Kek128::try_from(&kek[..15]).err()
+        );
+        assert_eq!(&[0u8; 24][..], Kek192::from([0u8; 24]).as_bytes());
+        assert_eq!(
+            "Kek256(..redacted..)",
+            format!("{:?}", Kek256::from([0x42u8; 32]))
+        );
+    }
+
+    #[test]
+    fn test_kek_wrap_unwrap() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let kek = Kek256::try_from(kek.as_slice()).unwrap();
+        let cipher = hex::decode("64E8C3F9CE0F5BA263E9777905818A2A93C8191E7D6E8AE7").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        assert_eq!(cipher, kek.wrap_key(&plain).unwrap());
+        assert_eq!(&plain[..], kek.unwrap_key(&cipher).unwrap().expose_secret());
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let kek = Kek192::try_from(kek.as_slice()).unwrap();
+        let cipher = hex::decode("AFBEB0F07DFBF5419200F2CCB50BB24F").unwrap();
+        let plain = hex::decode("466F7250617369").unwrap();
+        assert_eq!(cipher, kek.wrap_key_with_pad(&plain).unwrap());
+        assert_eq!(
+            &plain[..],
+            kek.unwrap_key_with_pad(&cipher).unwrap().expose_secret()
+        );
+    }
+
+    #[test]
+    fn test_kek_unwrap_many() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let kek = Kek128::try_from(kek.as_slice()).unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let good = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let mut bad = good.clone();
+        bad[10] ^= 1;
+        let results = kek.unwrap_many(&[&good, &bad, &good[..8]]);
+        assert_eq!(3, results.len());
+        assert_eq!(&plain[..], results[0].as_ref().unwrap().expose_secret());
+        assert_eq!(
+            Some(KeyWrapError::IntegrityCheckFailed),
+            results[1].as_ref().err().copied()
+        );
+        assert_eq!(
+            Some(KeyWrapError::InvalidCiphertextLength(8)),
+            results[2].as_ref().err().copied()
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{AesKeyWrapper, Algorithm, KeyWrapError, KeyWrapper, UnwrappedKey};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use async_trait::async_trait;
+
+// Async KEK provider (`async` feature)
+//
+// Cloud KMS wrap/unwrap calls are network round trips, so their clients are
+// async. `KekProvider` is the async counterpart of `KeyWrapper`: services
+// hold a `Box<dyn KekProvider>` and switch between the in-process
+// `AesKeyWrapper` and a KMS client without touching call sites.
+
+/// Object-safe async wrap/unwrap interface
+#[async_trait]
+pub trait KekProvider: Send + Sync {
+    /// Algorithm used by `wrap` and `unwrap`
+    fn algorithm(&self) -> Algorithm;
+
+    /// Wrap key material
+    async fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError>;
+
+    /// Unwrap and integrity-check key material
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError>;
+}
+
+// Local wrapping never waits, the futures are ready on first poll
+#[async_trait]
+impl KekProvider for AesKeyWrapper {
+    fn algorithm(&self) -> Algorithm {
+        KeyWrapper::algorithm(self)
+    }
+
+    async fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.try_wrap(plaintext)
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        self.try_unwrap(wrapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_boxed_kek_provider() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let plain = hex::decode("466F7250617369").unwrap();
+        let provider: Box<dyn KekProvider> =
+            Box::new(AesKeyWrapper::new(&kek, Algorithm::Kwp).unwrap());
+        assert_eq!(Algorithm::Kwp, provider.algorithm());
+
+        let wrapped = block_on(provider.wrap(&plain)).unwrap();
+        assert_eq!(
+            hex::decode("AFBEB0F07DFBF5419200F2CCB50BB24F").unwrap(),
+            wrapped
+        );
+        assert_eq!(
+            &plain[..],
+            block_on(provider.unwrap(&wrapped)).unwrap().expose_secret()
+        );
+        assert_eq!(
+            Some(KeyWrapError::InvalidCiphertextLength(15)),
+            block_on(provider.unwrap(&wrapped[..15])).err()
+        );
+    }
+}
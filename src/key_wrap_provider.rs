@@ -0,0 +1,121 @@
+#[cfg(feature = "std")]
+use crate::{BlockCipher128, BlockCipherKw};
+use crate::{Kek128, Kek192, Kek256, KeyWrapError, UnwrappedKey};
+use alloc::vec::Vec;
+
+// KW and KWP behind one object-safe trait
+//
+// `KeyWrapper` is bound to one algorithm per instance; frameworks that pick
+// RFC3394 or RFC5649 per call take a `Box<dyn KeyWrapProvider>` instead. The
+// typed KEKs are the software implementation, and with `std` a
+// `Mutex<BlockCipherKw<C>>` serves any `BlockCipher128` (a PKCS#11 token,
+// AF_ALG, a peripheral). HSM, TPM or KMS clients implement it directly.
+
+/// Object-safe RFC3394 / RFC5649 wrap and unwrap
+pub trait KeyWrapProvider {
+    /// Wrap key with the IV defined in RFC3394
+    fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError>;
+
+    /// Unwrap key and Check IV in RFC3394
+    fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError>;
+
+    /// Wrap key with pad using padding algorithm (RFC5649)
+    fn wrap_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError>;
+
+    /// Unwrap key with pad using padding algorithm (RFC5649)
+    fn unwrap_with_pad(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError>;
+}
+
+macro_rules! impl_kek_provider {
+    ($kek:ident) => {
+        impl KeyWrapProvider for $kek {
+            fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+                self.wrap_key(plaintext)
+            }
+
+            fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+                self.unwrap_key(wrapped)
+            }
+
+            fn wrap_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+                self.wrap_key_with_pad(plaintext)
+            }
+
+            fn unwrap_with_pad(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+                self.unwrap_key_with_pad(wrapped)
+            }
+        }
+    };
+}
+
+impl_kek_provider!(Kek128);
+impl_kek_provider!(Kek192);
+impl_kek_provider!(Kek256);
+
+// BlockCipherKw needs `&mut`, the lock serializes callers. After a
+// `BlockCipherFailed`, `lock()?.take_error()` has the cipher's own error.
+#[cfg(feature = "std")]
+impl<C: BlockCipher128> KeyWrapProvider for std::sync::Mutex<BlockCipherKw<C>> {
+    fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        with_cipher(self, |kw| kw.wrap(plaintext))
+    }
+
+    fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        with_cipher(self, |kw| kw.unwrap(wrapped))
+    }
+
+    fn wrap_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        with_cipher(self, |kw| kw.wrap_with_pad(plaintext))
+    }
+
+    fn unwrap_with_pad(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        with_cipher(self, |kw| kw.unwrap_with_pad(wrapped))
+    }
+}
+
+// a poisoned lock means a block operation panicked part way through
+#[cfg(feature = "std")]
+fn with_cipher<C, T, F>(lock: &std::sync::Mutex<BlockCipherKw<C>>, f: F) -> Result<T, KeyWrapError>
+where
+    C: BlockCipher128,
+    F: FnOnce(&mut BlockCipherKw<C>) -> Result<T, KeyWrapError>,
+{
+    let mut kw = lock.lock().map_err(|_| KeyWrapError::BlockCipherFailed)?;
+    f(&mut kw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+
+    // RFC3394 4.1 and an RFC5649 round trip through a boxed provider
+    fn check(provider: &dyn KeyWrapProvider) {
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        assert_eq!(cipher, provider.wrap(&plain).unwrap());
+        assert_eq!(
+            &plain[..],
+            provider.unwrap(&cipher).unwrap().expose_secret()
+        );
+        let wrapped = provider.wrap_with_pad(&plain[..7]).unwrap();
+        assert_eq!(
+            &plain[..7],
+            provider.unwrap_with_pad(&wrapped).unwrap().expose_secret()
+        );
+        assert_eq!(
+            Some(KeyWrapError::IntegrityCheckFailed),
+            provider.unwrap_with_pad(&cipher).err()
+        );
+    }
+
+    #[test]
+    fn test_providers() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        check(&Kek128::try_from(&kek[..]).unwrap());
+        #[cfg(feature = "std")]
+        check(&std::sync::Mutex::new(BlockCipherKw::new(
+            <crate::backend::Aes128Ecb as crate::backend::AesEcb>::try_new(&kek).unwrap(),
+        )));
+    }
+}
@@ -0,0 +1,246 @@
+use crate::locked::{locked_copy, LockedBuf};
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, Algorithm,
+    KeyWrapError, UnwrappedKey,
+};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Object-safe wrap/unwrap interface
+///
+/// Applications can hold a `Box<dyn KeyWrapper>` and inject the software
+/// implementation below, an HSM-backed one or a remote client at runtime.
+pub trait KeyWrapper {
+    /// Algorithm used by `wrap` and `unwrap`
+    fn algorithm(&self) -> Algorithm;
+
+    /// Wrap key material
+    fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError>;
+
+    /// Unwrap and integrity-check key material
+    fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError>;
+}
+
+/// In-process `KeyWrapper` using this crate's AES key wrap
+///
+/// The KEK copy is zeroized on drop. With the `mlock` feature, cloning
+/// panics if the clone's KEK buffer can't be locked.
+pub struct AesKeyWrapper {
+    kek: LockedBuf,
+    algorithm: Algorithm,
+    max_input_len: Option<usize>,
+    aad: Vec<u8>,
+}
+
+// the KEK goes through `locked_copy` like in `new`, not `Vec::clone`
+impl Clone for AesKeyWrapper {
+    fn clone(&self) -> Self {
+        AesKeyWrapper {
+            kek: locked_copy(&self.kek).expect("failed to lock the cloned KEK"),
+            algorithm: self.algorithm,
+            max_input_len: self.max_input_len,
+            aad: self.aad.clone(),
+        }
+    }
+}
+
+impl AesKeyWrapper {
+    /// Create a wrapper over a 128, 192 or 256-bit KEK
+    pub fn new(kek: &[u8], algorithm: Algorithm) -> Result<AesKeyWrapper, String> {
+        match kek.len() {
+            16 | 24 | 32 => Ok(AesKeyWrapper {
+                kek: locked_copy(kek)?,
+                algorithm,
+                max_input_len: None,
+                aad: Vec::new(),
+            }),
+            _ => Err(format!("kek is not supported: {} bytes", kek.len())),
+        }
+    }
+
+    /// Reject plaintexts and wrapped blobs longer than `max_input_len` bytes
+    /// before any work is done, e.g. when unwrapping attacker-supplied input
+    pub fn with_max_input_len(mut self, max_input_len: usize) -> AesKeyWrapper {
+        self.max_input_len = Some(max_input_len);
+        self
+    }
+
+    /// Additional authenticated data bound to every wrap and unwrap, for
+    /// the AEAD algorithms only
+    pub fn with_aad(mut self, aad: &[u8]) -> Result<AesKeyWrapper, String> {
+        if matches!(self.algorithm, Algorithm::Kw | Algorithm::Kwp) {
+            return Err(format!("aad is not supported with {:?}", self.algorithm));
+        }
+        self.aad = aad.to_vec();
+        Ok(self)
+    }
+
+    /// Wrap with the configured algorithm, see `KeyWrapper::wrap`
+    pub fn try_wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.check_input_len(plaintext.len())?;
+        match self.algorithm {
+            Algorithm::Kw => try_wrap_key(&self.kek, plaintext),
+            Algorithm::Kwp => try_wrap_key_with_pad(&self.kek, plaintext),
+            #[cfg(feature = "gcm-siv")]
+            Algorithm::AesGcmSiv => {
+                crate::try_aes_gcm_siv_wrap_key(&self.kek, plaintext, &self.aad)
+            }
+            #[cfg(feature = "gcm")]
+            Algorithm::AesGcm => crate::try_aes_gcm_wrap_key(&self.kek, plaintext, &self.aad),
+            #[cfg(feature = "chacha")]
+            Algorithm::ChaCha20Poly1305 => {
+                crate::try_chacha20poly1305_wrap_key(&self.kek, plaintext, &self.aad)
+            }
+        }
+    }
+
+    /// Unwrap with the configured algorithm, see `KeyWrapper::unwrap`
+    pub fn try_unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        self.check_input_len(wrapped.len())?;
+        match self.algorithm {
+            Algorithm::Kw => try_unwrap_key(&self.kek, wrapped),
+            Algorithm::Kwp => try_unwrap_key_with_pad(&self.kek, wrapped),
+            #[cfg(feature = "gcm-siv")]
+            Algorithm::AesGcmSiv => {
+                crate::try_aes_gcm_siv_unwrap_key(&self.kek, wrapped, &self.aad)
+            }
+            #[cfg(feature = "gcm")]
+            Algorithm::AesGcm => crate::try_aes_gcm_unwrap_key(&self.kek, wrapped, &self.aad),
+            #[cfg(feature = "chacha")]
+            Algorithm::ChaCha20Poly1305 => {
+                crate::try_chacha20poly1305_unwrap_key(&self.kek, wrapped, &self.aad)
+            }
+        }
+    }
+
+    fn check_input_len(&self, len: usize) -> Result<(), KeyWrapError> {
+        match self.max_input_len {
+            Some(max) if len > max => Err(KeyWrapError::LengthOverflow(len)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for AesKeyWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AesKeyWrapper")
+            .field("algorithm", &self.algorithm)
+            .field(
+                "kek",
+                &format_args!("Kek{}(..redacted..)", self.kek.len() * 8),
+            )
+            .field("max_input_len", &self.max_input_len)
+            .finish()
+    }
+}
+
+impl KeyWrapper for AesKeyWrapper {
+    fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.try_wrap(plaintext)
+    }
+
+    fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        self.try_unwrap(wrapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boxed_key_wrappers() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let plain = hex::decode("466F7250617369").unwrap();
+        let wrappers: Vec<Box<dyn KeyWrapper>> = vec![
+            Box::new(AesKeyWrapper::new(&kek, Algorithm::Kwp).unwrap()),
+            Box::new(AesKeyWrapper::new(&kek[..16], Algorithm::Kwp).unwrap()),
+        ];
+        for wrapper in &wrappers {
+            let wrapped = wrapper.wrap(&plain).unwrap();
+            assert_eq!(
+                &plain[..],
+                wrapper.unwrap(&wrapped).unwrap().expose_secret()
+            );
+        }
+        assert_eq!(
+            hex::decode("AFBEB0F07DFBF5419200F2CCB50BB24F").unwrap(),
+            wrappers[0].wrap(&plain).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_max_input_len() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let wrapper = AesKeyWrapper::new(&kek, Algorithm::Kwp)
+            .unwrap()
+            .with_max_input_len(16);
+        let wrapped = wrapper.wrap(&[7u8; 8]).unwrap();
+        assert_eq!(&[7u8; 8], wrapper.unwrap(&wrapped).unwrap().expose_secret());
+        assert!(wrapper.wrap(&[7u8; 17]).is_err());
+        assert_eq!(
+            Some(KeyWrapError::LengthOverflow(24)),
+            wrapper.unwrap(&[0u8; 24]).err()
+        );
+    }
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let wrapper = AesKeyWrapper::new(&[0x42u8; 32], Algorithm::Kw).unwrap();
+        assert_eq!(
+            "AesKeyWrapper { algorithm: Kw, kek: Kek256(..redacted..), max_input_len: None }",
+            format!("{:?}", wrapper)
+        );
+    }
+
+    #[cfg(feature = "gcm-siv")]
+    #[test]
+    fn test_aead_aad() {
+        let kek = [0x42u8; 32];
+        let wrapper = AesKeyWrapper::new(&kek, Algorithm::AesGcmSiv)
+            .unwrap()
+            .with_aad(b"ctx")
+            .unwrap();
+        let wrapped = wrapper.wrap(&[7u8; 16]).unwrap();
+        assert_eq!(
+            crate::try_aes_gcm_siv_wrap_key(&kek, &[7u8; 16], b"ctx").unwrap(),
+            wrapped
+        );
+        assert_eq!(
+            &[7u8; 16],
+            wrapper.unwrap(&wrapped).unwrap().expose_secret()
+        );
+        let other = AesKeyWrapper::new(&kek, Algorithm::AesGcmSiv).unwrap();
+        assert!(other.unwrap(&wrapped).is_err());
+    }
+
+    #[test]
+    fn test_aad_rejected_for_kw() {
+        let wrapper = AesKeyWrapper::new(&[0u8; 16], Algorithm::Kw).unwrap();
+        assert!(wrapper.with_aad(b"ctx").is_err());
+    }
+
+    #[test]
+    fn test_clone() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let wrapper = AesKeyWrapper::new(&kek, Algorithm::Kwp)
+            .unwrap()
+            .with_max_input_len(16);
+        let clone = wrapper.clone();
+        drop(wrapper);
+        let wrapped = clone.wrap(&[7u8; 8]).unwrap();
+        assert_eq!(&[7u8; 8], clone.unwrap(&wrapped).unwrap().expose_secret());
+        assert!(clone.wrap(&[7u8; 17]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_kek_length() {
+        assert!(AesKeyWrapper::new(&[0u8; 20], Algorithm::Kw).is_err());
+    }
+}
@@ -1,8 +1,186 @@
-#![feature(test)]
-extern crate test;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![forbid(unsafe_code)]
+extern crate alloc;
 
-use crypto2::blockmode::{Aes128Ecb, Aes192Ecb, Aes256Ecb};
-use std::io::Write;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use backend::{Aes128Ecb, Aes192Ecb, Aes256Ecb, AesEcb};
+use bytes::{to_u8_4_array, u32_from_be_u8};
+use core::fmt;
+use locked::{locked_copy, LockedBuf};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, Zeroizing};
+
+#[cfg(all(feature = "af-alg", any(target_os = "linux", target_os = "android")))]
+mod af_alg_cipher;
+#[cfg(feature = "akw")]
+mod akw;
+#[cfg(feature = "aria-kw")]
+mod aria_kw;
+mod backend;
+#[cfg(all(feature = "rayon", feature = "std"))]
+mod batch;
+mod block_cipher;
+pub mod bytes;
+#[cfg(feature = "camellia-kw")]
+mod camellia_kw;
+#[cfg(feature = "chacha")]
+mod chacha;
+#[cfg(feature = "cipher")]
+mod cipher_kek;
+#[cfg(any(feature = "akw", feature = "hmac-wrap"))]
+mod cms_tdes;
+#[cfg(kw_backend = "cng")]
+mod cng_aes;
+#[cfg(kw_backend = "commoncrypto")]
+mod commoncrypto_aes;
+#[cfg(all(feature = "serde", feature = "std"))]
+mod config;
+#[cfg(feature = "std")]
+mod dek_cache;
+#[cfg(feature = "std")]
+mod env_file;
+#[cfg(feature = "std")]
+mod env_secret;
+mod error;
+mod fixed;
+#[cfg(feature = "gcm")]
+mod gcm;
+#[cfg(feature = "gcm-siv")]
+mod gcm_siv;
+#[cfg(feature = "hmac-wrap")]
+mod hmac_wrap;
+mod kek;
+#[cfg(feature = "async")]
+mod kek_provider;
+mod key_wrap_provider;
+mod key_wrapper;
+mod locked;
+#[cfg(feature = "mobile")]
+pub mod mobile;
+#[cfg(all(any(test, feature = "mock"), feature = "std"))]
+mod mock;
+mod multilane;
+mod negative;
+#[cfg(feature = "node")]
+pub mod node;
+#[cfg(kw_backend = "openssl")]
+mod openssl_aes;
+#[cfg(feature = "pkcs11")]
+mod pkcs11_cipher;
+pub mod pkcs11_uri;
+#[cfg(feature = "python")]
+mod python;
+mod registry;
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(kw_backend = "aes")]
+mod rustcrypto_aes;
+mod scratch;
+#[cfg(feature = "secrecy")]
+mod secret_kek;
+#[cfg(feature = "seed-kw")]
+mod seed_kw;
+mod self_test;
+#[cfg(kw_backend = "self-contained")]
+mod soft_aes;
+#[cfg(all(test, feature = "rand_core"))]
+mod test_rng;
+#[cfg(feature = "tkw")]
+mod tkw;
+mod unwrapped_key;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod wrap_request;
+
+#[cfg(all(feature = "af-alg", any(target_os = "linux", target_os = "android")))]
+pub use af_alg_cipher::AfAlgCipher;
+#[cfg(feature = "akw")]
+pub use akw::{akw1_unwrap_key, akw1_wrap_key};
+#[cfg(feature = "aria-kw")]
+pub use aria_kw::{
+    aria_unwrap_key, aria_unwrap_key_with_pad, aria_wrap_key, aria_wrap_key_with_pad,
+    try_aria_unwrap_key, try_aria_unwrap_key_with_pad, try_aria_wrap_key,
+    try_aria_wrap_key_with_pad,
+};
+pub use backend::aes_backend;
+#[cfg(all(feature = "rayon", feature = "std"))]
+pub use batch::{
+    unwrap_keys_batch, unwrap_keys_with_pad_batch, wrap_keys_batch, wrap_keys_with_pad_batch,
+};
+pub use block_cipher::{BlockCipher128, BlockCipherKw};
+#[cfg(feature = "camellia-kw")]
+pub use camellia_kw::{
+    camellia_unwrap_key, camellia_wrap_key, try_camellia_unwrap_key, try_camellia_wrap_key,
+    ID_CAMELLIA128_WRAP, ID_CAMELLIA192_WRAP, ID_CAMELLIA256_WRAP,
+};
+#[cfg(feature = "chacha")]
+pub use chacha::{
+    try_chacha20poly1305_unwrap_key, try_chacha20poly1305_wrap_key,
+    try_chacha20poly1305_wrap_key_with_rng,
+};
+#[cfg(feature = "cipher")]
+pub use cipher_kek::Kek;
+#[cfg(all(feature = "serde", feature = "std"))]
+pub use config::{
+    clear_config_key_wrapper, deserialize_wrapped, register_config_key_wrapper, ConfigSecret,
+};
+#[cfg(feature = "std")]
+pub use dek_cache::DekCache;
+#[cfg(feature = "std")]
+pub use env_file::WrappedEnvFile;
+#[cfg(feature = "std")]
+pub use env_secret::{EnvSecret, ZeroizingString, ENV_TOKEN_PREFIX};
+pub use error::KeyWrapError;
+pub use fixed::{unwrap_fixed, unwrap_fixed_with_pad, wrap_fixed, wrap_fixed_with_pad};
+#[cfg(feature = "gcm")]
+pub use gcm::{try_aes_gcm_unwrap_key, try_aes_gcm_wrap_key, try_aes_gcm_wrap_key_with_rng};
+#[cfg(feature = "gcm-siv")]
+pub use gcm_siv::{try_aes_gcm_siv_unwrap_key, try_aes_gcm_siv_wrap_key};
+#[cfg(feature = "hmac-wrap")]
+pub use hmac_wrap::{
+    hmac_unwrap_key_aes, hmac_unwrap_key_tdes, hmac_wrap_key_aes, hmac_wrap_key_tdes,
+    ID_ALG_HMAC_WITH_3DES_WRAP, ID_ALG_HMAC_WITH_AES_WRAP,
+};
+pub use kek::{Kek128, Kek192, Kek256};
+#[cfg(feature = "async")]
+pub use kek_provider::KekProvider;
+pub use key_wrap_provider::KeyWrapProvider;
+pub use key_wrapper::{AesKeyWrapper, KeyWrapper};
+#[cfg(all(any(test, feature = "mock"), feature = "std"))]
+pub use mock::{MockCall, MockKeyWrapper};
+pub use multilane::{unwrap_keys_multilane, wrap_keys_multilane};
+pub use negative::{
+    kw_negative_vectors, kwp_negative_vectors, ExpectedFailure, NegativeVector, Tamper,
+};
+#[cfg(feature = "pkcs11")]
+pub use pkcs11_cipher::Pkcs11Cipher;
+pub use registry::AlgorithmId;
+#[cfg(feature = "std")]
+pub use retry::{ResilientKeyWrapper, RetryPolicy};
+pub use scratch::WrapScratch;
+#[cfg(feature = "secrecy")]
+pub use secret_kek::SecretKek;
+#[cfg(feature = "seed-kw")]
+pub use seed_kw::{
+    seed_unwrap_key, seed_wrap_key, try_seed_unwrap_key, try_seed_wrap_key,
+    ID_NPKI_APP_CMS_SEED_WRAP,
+};
+pub use self_test::{run_self_tests, SelfTestError, SelfTestReport};
+#[cfg(feature = "tkw")]
+pub use tkw::{tdes_unwrap_key, tdes_wrap_key, try_tdes_unwrap_key, try_tdes_wrap_key};
+pub use unwrapped_key::UnwrappedKey;
+pub use wrap_request::WrapRequest;
+
+#[cfg(all(kw_backend = "crypto2", not(feature = "crypto2")))]
+compile_error!(
+    "enable one of the `backend-aes` (default), `backend-crypto2`, `backend-openssl`, \
+     `backend-cng`, `backend-commoncrypto` or `self-contained` features"
+);
+
+#[cfg(feature = "mobile")]
+uniffi::setup_scaffolding!("aes_keywrap");
 
 // constants for initial value in primary (RFC3394) and extended (RFC5649) definition
 /// Initial value from RFC3394 Section 2.2.3.1
@@ -12,6 +190,29 @@ pub const IV_3394: [u8; 8] = [0xa6, 0xa6, 0xa6, 0xa6, 0xa6, 0xa6, 0xa6, 0xa6];
 /// http://www.ietf.org/rfc/rfc5649.txt
 pub const IV_5649: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
 
+/// Key wrap algorithms implemented by this crate
+///
+/// More variants are enabled by the `gcm-siv`, `gcm` and `chacha` features,
+/// so matches need a wildcard arm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// AES Key Wrap (RFC3394)
+    Kw,
+    /// AES Key Wrap with Padding (RFC5649)
+    Kwp,
+    /// AES-GCM-SIV (RFC 8452) with a fixed nonce, 128 or 256-bit KEK
+    #[cfg(feature = "gcm-siv")]
+    AesGcmSiv,
+    /// AES-GCM with a random nonce stored in front of the ciphertext
+    #[cfg(feature = "gcm")]
+    AesGcm,
+    /// ChaCha20-Poly1305 (RFC 8439) with an HKDF-derived nonce, 256-bit KEK
+    #[cfg(feature = "chacha")]
+    ChaCha20Poly1305,
+}
+
 // See the AES Key Wrap definition RFC and update
 // * RFC3394 "Advanced Encryption Standard (AES) Key Wrap Algorithm"
 //   https://tools.ietf.org/html/rfc3394.html
@@ -20,74 +221,60 @@ pub const IV_5649: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
 //   https://tools.ietf.org/html/rfc5649.html
 //   (algorithm not repeated here, relatively minor additions)
 
-#[inline(always)]
-fn to_u8_8_array(array: &[u8]) -> [u8; 8] {
-    unsafe { *(array as *const _ as *const _) }
-}
-
-#[inline(always)]
-fn to_u8_4_array(array: &[u8]) -> [u8; 4] {
-    unsafe { *(array as *const _ as *const _) }
-}
-
-#[inline(always)]
-fn u32_from_be_u8(buffer: &[u8; 4]) -> u32 {
-    u32::from(buffer[3])
-        | u32::from(buffer[2]) << 8
-        | u32::from(buffer[1]) << 16
-        | u32::from(buffer[0]) << 24
-}
-
-#[inline(always)]
-fn u64_from_be_u8(buffer: &[u8; 8]) -> u64 {
-    u64::from(buffer[7])
-        | u64::from(buffer[6]) << 8
-        | u64::from(buffer[5]) << 16
-        | u64::from(buffer[4]) << 24
-        | u64::from(buffer[3]) << 32
-        | u64::from(buffer[2]) << 40
-        | u64::from(buffer[1]) << 48
-        | u64::from(buffer[0]) << 56
-}
-
 /// Unwrap key and Check IV in RFC3394
 pub fn aes_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
-    let (key, key_iv) = aes_unwrap_key_and_iv(kek, wrapped)?;
-    if key_iv != IV_3394 {
+    let (mut key, key_iv) = aes_unwrap_key_and_iv(kek, wrapped)?;
+    if !bool::from(key_iv.ct_eq(&IV_3394[..])) {
+        key.zeroize();
         return Err(String::from("Key IV error"));
     }
 
     Ok(key)
 }
 
+/// Unwrap a batch of keys (RFC3394), reporting each item's outcome separately
+/// so one corrupted blob doesn't stop the others from loading
+#[deprecated(
+    note = "returns the keys unzeroized and the errors as strings; use `unwrap_many` on `Kek128/192/256` or `Aes*Kw` instead"
+)]
+pub fn aes_unwrap_keys<T: AsRef<[u8]>>(kek: &[u8], wrapped: &[T]) -> Vec<Result<Vec<u8>, String>> {
+    wrapped
+        .iter()
+        .map(|w| aes_unwrap_key(kek, w.as_ref()))
+        .collect()
+}
+
 /// Unwrap and return the key and IV
 pub fn aes_unwrap_key_and_iv(kek: &[u8], wrapped: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    self_test::power_up()?;
     match kek.len() {
-        16 => Aes128Kw::aes_unwrap_key_and_iv(kek, wrapped),
-        24 => Aes192Kw::aes_unwrap_key_and_iv(kek, wrapped),
-        32 => Aes256Kw::aes_unwrap_key_and_iv(kek, wrapped),
-        _ => Err(format!("kek is not supported: {:?}", kek)),
+        16 => Aes128Kw::unwrap_key_and_iv(kek, wrapped),
+        24 => Aes192Kw::unwrap_key_and_iv(kek, wrapped),
+        32 => Aes256Kw::unwrap_key_and_iv(kek, wrapped),
+        len => Err(KeyWrapError::InvalidKekLength(len).into()),
     }
 }
 
-/// Unwrap key with pad using padding algorithm (RFC5649) 
+/// Unwrap key with pad using padding algorithm (RFC5649)
 #[inline]
 pub fn aes_unwrap_key_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    self_test::power_up()?;
     match kek.len() {
-        16 => Aes128Kw::aes_unwrap_key_with_pad(kek, wrapped),
-        24 => Aes192Kw::aes_unwrap_key_with_pad(kek, wrapped),
-        32 => Aes256Kw::aes_unwrap_key_with_pad(kek, wrapped),
-        _ => Err(format!("kek is not supported: {:?}", kek)),
+        16 => Aes128Kw::unwrap_key_with_pad(kek, wrapped),
+        24 => Aes192Kw::unwrap_key_with_pad(kek, wrapped),
+        32 => Aes256Kw::unwrap_key_with_pad(kek, wrapped),
+        len => Err(KeyWrapError::InvalidKekLength(len).into()),
     }
 }
 
 /// Wrap key with specific IV
 pub fn aes_wrap_key_and_iv(kek: &[u8], plaintext: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+    self_test::power_up()?;
     match kek.len() {
-        16 => Aes128Kw::aes_wrap_key_and_iv(kek, plaintext, iv),
-        24 => Aes192Kw::aes_wrap_key_and_iv(kek, plaintext, iv),
-        32 => Aes256Kw::aes_wrap_key_and_iv(kek, plaintext, iv),
-        _ => Err(format!("kek is not supported: {:?}", kek)),
+        16 => Aes128Kw::wrap_key_and_iv(kek, plaintext, iv),
+        24 => Aes192Kw::wrap_key_and_iv(kek, plaintext, iv),
+        32 => Aes256Kw::wrap_key_and_iv(kek, plaintext, iv),
+        len => Err(KeyWrapError::InvalidKekLength(len).into()),
     }
 }
 
@@ -96,134 +283,893 @@ pub fn aes_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
     aes_wrap_key_and_iv(kek, plaintext, &IV_3394)
 }
 
-/// Wrap key with pad using padding algorithm (RFC5649) 
+/// Wrap key with pad using padding algorithm (RFC5649)
 pub fn aes_wrap_key_with_pad(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    self_test::power_up()?;
     match kek.len() {
-        16 => Aes128Kw::aes_wrap_key_with_pad(kek, plaintext),
-        24 => Aes192Kw::aes_wrap_key_with_pad(kek, plaintext),
-        32 => Aes256Kw::aes_wrap_key_with_pad(kek, plaintext),
-        _ => Err(format!("kek is not supported: {:?}", kek)),
+        16 => Aes128Kw::wrap_key_with_pad(kek, plaintext),
+        24 => Aes192Kw::wrap_key_with_pad(kek, plaintext),
+        32 => Aes256Kw::wrap_key_with_pad(kek, plaintext),
+        len => Err(KeyWrapError::InvalidKekLength(len).into()),
+    }
+}
+
+/// Wrap key with pad (RFC5649) using your own AIV constant, see
+/// `try_wrap_key_with_pad_and_aiv`
+pub fn aes_wrap_key_with_pad_and_aiv(
+    kek: &[u8],
+    plaintext: &[u8],
+    aiv: &[u8; 4],
+) -> Result<Vec<u8>, String> {
+    Ok(try_wrap_key_with_pad_and_aiv(kek, plaintext, aiv)?)
+}
+
+/// Unwrap key with pad (RFC5649) and Check it carries `expected_aiv`
+pub fn aes_unwrap_key_with_pad_and_aiv(
+    kek: &[u8],
+    wrapped: &[u8],
+    expected_aiv: &[u8; 4],
+) -> Result<UnwrappedKey, String> {
+    Ok(try_unwrap_key_with_pad_and_aiv(kek, wrapped, expected_aiv)?)
+}
+
+// In-place W / W^-1 dispatch on KEK size, see `wrap_in_place` below
+fn wrap_in_place(kek: &[u8], buf: &mut [u8]) -> Result<(), KeyWrapError> {
+    self_test::power_up()?;
+    match kek.len() {
+        16 => Aes128Kw::wrap_in_place(kek, buf),
+        24 => Aes192Kw::wrap_in_place(kek, buf),
+        32 => Aes256Kw::wrap_in_place(kek, buf),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+fn unwrap_in_place(kek: &[u8], buf: &mut [u8]) -> Result<(), KeyWrapError> {
+    self_test::power_up()?;
+    match kek.len() {
+        16 => Aes128Kw::unwrap_in_place(kek, buf),
+        24 => Aes192Kw::unwrap_in_place(kek, buf),
+        32 => Aes256Kw::unwrap_in_place(kek, buf),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+// NIST SP 800-38F section 5.3.1 limits
+const KW_MAX_SEMIBLOCKS: u64 = 1 << 54;
+const KWP_MAX_PLAINTEXT_LEN: u64 = u32::MAX as u64;
+
+fn check_kw_plaintext_len(len: usize) -> Result<(), KeyWrapError> {
+    if len < 16 || !len.is_multiple_of(8) {
+        return Err(KeyWrapError::InvalidPlaintextLength(len));
+    }
+    if len as u64 / 8 > KW_MAX_SEMIBLOCKS {
+        return Err(KeyWrapError::LengthOverflow(len));
+    }
+    Ok(())
+}
+
+fn check_kw_wrapped_len(len: usize) -> Result<(), KeyWrapError> {
+    if len < 24 || !len.is_multiple_of(8) {
+        return Err(KeyWrapError::InvalidCiphertextLength(len));
+    }
+    if len as u64 / 8 - 1 > KW_MAX_SEMIBLOCKS {
+        return Err(KeyWrapError::LengthOverflow(len));
+    }
+    Ok(())
+}
+
+fn check_kwp_plaintext_len(len: usize) -> Result<(), KeyWrapError> {
+    if len == 0 {
+        return Err(KeyWrapError::InvalidPlaintextLength(len));
+    }
+    if len as u64 > KWP_MAX_PLAINTEXT_LEN {
+        return Err(KeyWrapError::LengthOverflow(len));
+    }
+    Ok(())
+}
+
+fn check_kwp_wrapped_len(len: usize) -> Result<(), KeyWrapError> {
+    if len < 16 || !len.is_multiple_of(8) {
+        return Err(KeyWrapError::InvalidCiphertextLength(len));
+    }
+    if len as u64 - 8 > KWP_MAX_PLAINTEXT_LEN.div_ceil(8) * 8 {
+        return Err(KeyWrapError::LengthOverflow(len));
     }
+    Ok(())
+}
+
+// Fallible API
+//
+// Same algorithms as above, but every malformed input (KEK, plaintext or
+// wrapped length, MLI, padding) is reported as a `KeyWrapError` instead of
+// a message string or a panic in slice arithmetic. Working buffers holding
+// plaintext are zeroized before they are dropped.
+
+/// Wrap key with the IV defined in RFC3394
+pub fn try_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    check_kek_len(kek)?;
+    kw_wrap_with(plaintext, |buf| wrap_in_place(kek, buf))
+}
+
+/// Unwrap key and Check IV in RFC3394
+pub fn try_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    let buf = unwrap_kw_checked(wrapped, |buf| unwrap_in_place(kek, buf))?;
+    UnwrappedKey::from_slice(&buf[8..])
+}
+
+/// Wrap key with pad using padding algorithm (RFC5649)
+pub fn try_wrap_key_with_pad(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    check_kek_len(kek)?;
+    kwp_wrap_with(plaintext, |buf| wrap_in_place(kek, buf))
+}
+
+/// Unwrap key with pad using padding algorithm (RFC5649)
+pub fn try_unwrap_key_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    let (buf, key_len) = unwrap_kwp_checked(wrapped, |buf| unwrap_in_place(kek, buf))?;
+    UnwrappedKey::from_slice(&buf[8..8 + key_len])
+}
+
+/// Wrap key with pad (RFC5649) using a 4-octet AIV constant other than
+/// A65959A6, as RFC5649 section 3 allows for other uses; the MLI follows
+/// it as usual
+pub fn try_wrap_key_with_pad_and_aiv(
+    kek: &[u8],
+    plaintext: &[u8],
+    aiv: &[u8; 4],
+) -> Result<Vec<u8>, KeyWrapError> {
+    check_kek_len(kek)?;
+    kwp_wrap_with_aiv(plaintext, aiv, |buf| wrap_in_place(kek, buf))
+}
+
+/// Unwrap key with pad (RFC5649), checking the AIV constant against
+/// `expected_aiv` along with the MLI and padding
+pub fn try_unwrap_key_with_pad_and_aiv(
+    kek: &[u8],
+    wrapped: &[u8],
+    expected_aiv: &[u8; 4],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    let (buf, key_len) =
+        unwrap_kwp_checked_with_aiv(wrapped, expected_aiv, |buf| unwrap_in_place(kek, buf))?;
+    UnwrappedKey::from_slice(&buf[8..8 + key_len])
+}
+
+// The length checks and RFC3394/RFC5649 framing around a W (`w`) or W^-1
+// (`w_inv`) step, shared by the functions above and the `Aes*Kw` contexts
+
+fn kw_wrap_with<F>(plaintext: &[u8], w: F) -> Result<Vec<u8>, KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kw_plaintext_len(plaintext.len())?;
+
+    let mut buf: Vec<u8> = Vec::with_capacity(plaintext.len() + 8);
+    buf.extend_from_slice(&IV_3394);
+    buf.extend_from_slice(plaintext);
+    w(&mut buf)?;
+    Ok(buf)
+}
+
+fn kwp_wrap_with<F>(plaintext: &[u8], w: F) -> Result<Vec<u8>, KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    kwp_wrap_with_aiv(plaintext, &IV_5649, w)
+}
+
+fn kwp_wrap_with_aiv<F>(plaintext: &[u8], aiv: &[u8; 4], w: F) -> Result<Vec<u8>, KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kwp_plaintext_len(plaintext.len())?;
+
+    let padded_len = plaintext.len().div_ceil(8) * 8;
+    let mut buf: Vec<u8> = Vec::with_capacity(padded_len + 8);
+    buf.extend_from_slice(aiv);
+    buf.extend_from_slice(&(plaintext.len() as u32).to_be_bytes());
+    buf.extend_from_slice(plaintext);
+    buf.resize(padded_len + 8, 0);
+    w(&mut buf)?;
+    Ok(buf)
+}
+
+// W^-1 and the ICV check; the returned buffer is ICV | key
+fn unwrap_kw_checked<F>(wrapped: &[u8], w_inv: F) -> Result<LockedBuf, KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kw_wrapped_len(wrapped.len())?;
+
+    let mut buf = locked_copy(wrapped)?;
+    w_inv(&mut buf)?;
+    check_kw_block(&buf)?;
+
+    Ok(buf)
+}
+
+// ICV check on an unwrapped ICV | key block
+fn check_kw_block(buf: &[u8]) -> Result<(), KeyWrapError> {
+    if !bool::from(buf[..8].ct_eq(&IV_3394[..])) {
+        return Err(KeyWrapError::IntegrityCheckFailed);
+    }
+    Ok(())
+}
+
+// W^-1 and the AIV, MLI and padding checks; the returned buffer is
+// AIV | MLI | key | padding, along with the key length
+fn unwrap_kwp_checked<F>(wrapped: &[u8], w_inv: F) -> Result<(LockedBuf, usize), KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    unwrap_kwp_checked_with_aiv(wrapped, &IV_5649, w_inv)
+}
+
+fn unwrap_kwp_checked_with_aiv<F>(
+    wrapped: &[u8],
+    aiv: &[u8; 4],
+    w_inv: F,
+) -> Result<(LockedBuf, usize), KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kwp_wrapped_len(wrapped.len())?;
+
+    let mut buf = locked_copy(wrapped)?;
+    w_inv(&mut buf)?;
+    let key_len = check_kwp_block_with_aiv(&buf, aiv)?;
+
+    Ok((buf, key_len))
+}
+
+// AIV, MLI and padding checks on an unwrapped AIV | MLI | key | padding
+// block, returning the key length
+fn check_kwp_block(buf: &[u8]) -> Result<usize, KeyWrapError> {
+    check_kwp_block_with_aiv(buf, &IV_5649)
+}
+
+fn check_kwp_block_with_aiv(buf: &[u8], aiv: &[u8; 4]) -> Result<usize, KeyWrapError> {
+    //RFC5649: 32bit fixed + 32bit length, 8*(n-1) < MLI <= 8*n, zero padding
+    let padded_len = buf.len() - 8;
+    let mli = u32_from_be_u8(&to_u8_4_array(&buf[4..8]));
+    let key_len = mli as usize;
+    if !bool::from(buf[..4].ct_eq(&aiv[..])) {
+        return Err(KeyWrapError::IntegrityCheckFailed);
+    }
+    if key_len <= padded_len - 8 || key_len > padded_len {
+        return Err(KeyWrapError::InvalidMli(mli));
+    }
+    if buf[8 + key_len..].iter().any(|b| *b != 0) {
+        return Err(KeyWrapError::InvalidPadding);
+    }
+
+    Ok(key_len)
+}
+
+// Verify-only unwrap
+//
+// Runs W^-1 and every integrity check of the `try_*` unwrap functions in a
+// locked working buffer that is zeroized on return; the recovered key is
+// never copied out. For health checks and escrow audits where the caller
+// must not see key bytes.
+
+/// Check that `wrapped` unwraps (RFC3394) under `kek` without returning the key
+pub fn aes_verify_wrapped(kek: &[u8], wrapped: &[u8]) -> Result<(), KeyWrapError> {
+    unwrap_kw_checked(wrapped, |buf| unwrap_in_place(kek, buf)).map(|_| ())
+}
+
+/// Check that `wrapped` unwraps (RFC5649) under `kek` without returning the key
+pub fn aes_verify_wrapped_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<(), KeyWrapError> {
+    unwrap_kwp_checked(wrapped, |buf| unwrap_in_place(kek, buf)).map(|_| ())
+}
+
+// Wrapping function W
+//
+// SP 800-38F defines KW as W over ICV1 | P and KWP as W over
+// ICV2 | MLI | padded P. `w` and `w_inverse` are that W step alone, over a
+// slice of 8-byte semiblocks, so other initial values can be built on top.
+
+/// The SP 800-38F wrapping function W over the semiblocks S1 | ... | Sn
+/// (n >= 3), in place
+pub fn w(kek: &[u8], s: &mut [[u8; 8]]) -> Result<(), KeyWrapError> {
+    if !w_semiblocks_ok(s.len()) {
+        return Err(KeyWrapError::InvalidPlaintextLength(s.len() * 8));
+    }
+    wrap_in_place(kek, s.as_flattened_mut())
+}
+
+/// The SP 800-38F unwrapping function W^-1 over the semiblocks
+/// C1 | ... | Cn (n >= 3), in place
+///
+/// Nothing is checked: S1 and the rest of `s` are the caller's to verify.
+pub fn w_inverse(kek: &[u8], s: &mut [[u8; 8]]) -> Result<(), KeyWrapError> {
+    if !w_semiblocks_ok(s.len()) {
+        return Err(KeyWrapError::InvalidCiphertextLength(s.len() * 8));
+    }
+    unwrap_in_place(kek, s.as_flattened_mut())
+}
+
+// SP 800-38F section 6.1; two semiblocks would be the RFC5649 single-block
+// case, which is not W
+fn w_semiblocks_ok(n: usize) -> bool {
+    n >= 3 && n as u64 - 1 <= KW_MAX_SEMIBLOCKS
+}
+
+// In-place API
+//
+// Wrap and unwrap directly in a caller-owned buffer, with no output
+// allocation. The buffer starts with 8 spare bytes for the ICV/AIV, followed
+// by the plaintext (and, for RFC5649, room for the padding); unwrap leaves
+// the key inside the buffer and returns a slice of it. A buffer that fails
+// an integrity check is zeroized before the error is returned.
+
+/// Wrap (RFC3394) the plaintext in `buf[8..]` in place, returning the
+/// wrapped length (`buf.len()`)
+pub fn wrap_key_in_place(kek: &[u8], buf: &mut [u8]) -> Result<usize, KeyWrapError> {
+    check_kek_len(kek)?;
+    kw_wrap_in_place_with(buf, |buf| wrap_in_place(kek, buf))
+}
+
+/// Unwrap (RFC3394) `buf` in place, returning the key within it
+pub fn unwrap_key_in_place<'a>(kek: &[u8], buf: &'a mut [u8]) -> Result<&'a [u8], KeyWrapError> {
+    kw_unwrap_in_place_with(buf, |buf| unwrap_in_place(kek, buf))
+}
+
+/// Wrap (RFC5649) the `plaintext_len` octets at `buf[8..]` in place,
+/// returning the wrapped length
+///
+/// `buf` must hold at least `wrapped_len_with_pad(plaintext_len)` bytes;
+/// anything past the wrapped length is left untouched.
+pub fn wrap_key_with_pad_in_place(
+    kek: &[u8],
+    buf: &mut [u8],
+    plaintext_len: usize,
+) -> Result<usize, KeyWrapError> {
+    check_kek_len(kek)?;
+    kwp_wrap_in_place_with(buf, plaintext_len, |buf| wrap_in_place(kek, buf))
+}
+
+/// Unwrap (RFC5649) `buf` in place, returning the key within it
+pub fn unwrap_key_with_pad_in_place<'a>(
+    kek: &[u8],
+    buf: &'a mut [u8],
+) -> Result<&'a [u8], KeyWrapError> {
+    kwp_unwrap_in_place_with(buf, |buf| unwrap_in_place(kek, buf))
+}
+
+// The in-place framing around a W or W^-1 step, shared by the functions
+// above and `BlockCipherKw`
+
+fn kw_wrap_in_place_with<F>(buf: &mut [u8], w: F) -> Result<usize, KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kw_plaintext_len(buf.len().saturating_sub(8))?;
+
+    buf[..8].copy_from_slice(&IV_3394);
+    w(buf)?;
+    Ok(buf.len())
+}
+
+fn kw_unwrap_in_place_with<F>(buf: &mut [u8], w_inv: F) -> Result<&[u8], KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kw_wrapped_len(buf.len())?;
+
+    w_inv(buf)?;
+    if !bool::from(buf[..8].ct_eq(&IV_3394[..])) {
+        buf.zeroize();
+        return Err(KeyWrapError::IntegrityCheckFailed);
+    }
+
+    Ok(&buf[8..])
+}
+
+fn kwp_wrap_in_place_with<F>(
+    buf: &mut [u8],
+    plaintext_len: usize,
+    w: F,
+) -> Result<usize, KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kwp_plaintext_len(plaintext_len)?;
+    let wrapped_len = wrapped_len_with_pad(plaintext_len);
+    if buf.len() < wrapped_len {
+        return Err(KeyWrapError::BufferTooSmall(wrapped_len));
+    }
+
+    let buf = &mut buf[..wrapped_len];
+    buf[..4].copy_from_slice(&IV_5649);
+    buf[4..8].copy_from_slice(&(plaintext_len as u32).to_be_bytes());
+    for b in &mut buf[8 + plaintext_len..] {
+        *b = 0;
+    }
+    w(buf)?;
+    Ok(wrapped_len)
+}
+
+fn kwp_unwrap_in_place_with<F>(buf: &mut [u8], w_inv: F) -> Result<&[u8], KeyWrapError>
+where
+    F: FnOnce(&mut [u8]) -> Result<(), KeyWrapError>,
+{
+    check_kwp_wrapped_len(buf.len())?;
+
+    w_inv(buf)?;
+    match check_kwp_block(buf) {
+        Ok(key_len) => Ok(&buf[8..8 + key_len]),
+        Err(e) => {
+            buf.zeroize();
+            Err(e)
+        }
+    }
+}
+
+// Caller-provided output
+//
+// Wrap or unwrap into a caller's `out` slice and return the number of bytes
+// written; `out` may be longer than needed. Unwrap still runs W^-1 in a
+// locked working buffer zeroized on return, so only the key reaches `out`.
+
+/// Wrapped length (RFC3394) of a `plaintext_len` octet key
+pub const fn wrapped_len(plaintext_len: usize) -> usize {
+    plaintext_len + 8
+}
+
+/// Wrapped length (RFC5649) of a `plaintext_len` octet key
+pub const fn wrapped_len_with_pad(plaintext_len: usize) -> usize {
+    plaintext_len.div_ceil(8) * 8 + 8
+}
+
+/// Output size the `unwrap_*_into` functions need for a `wrapped_len`
+/// octet input (RFC5649 keys may turn out up to 7 octets shorter)
+pub fn unwrapped_len_max(wrapped_len: usize) -> usize {
+    wrapped_len.saturating_sub(8)
+}
+
+fn check_out_len(out: &[u8], needed: usize) -> Result<(), KeyWrapError> {
+    if out.len() < needed {
+        return Err(KeyWrapError::BufferTooSmall(needed));
+    }
+    Ok(())
+}
+
+/// Wrap key (RFC3394) into `out`, returning the wrapped length
+pub fn wrap_key_into(kek: &[u8], plaintext: &[u8], out: &mut [u8]) -> Result<usize, KeyWrapError> {
+    check_kek_len(kek)?;
+    check_kw_plaintext_len(plaintext.len())?;
+    let wrapped_len = wrapped_len(plaintext.len());
+    check_out_len(out, wrapped_len)?;
+
+    out[8..wrapped_len].copy_from_slice(plaintext);
+    wrap_key_in_place(kek, &mut out[..wrapped_len])
+}
+
+/// Unwrap key (RFC3394) into `out`, returning the key length
+pub fn unwrap_key_into(kek: &[u8], wrapped: &[u8], out: &mut [u8]) -> Result<usize, KeyWrapError> {
+    check_kw_wrapped_len(wrapped.len())?;
+    check_out_len(out, unwrapped_len_max(wrapped.len()))?;
+
+    let buf = unwrap_kw_checked(wrapped, |buf| unwrap_in_place(kek, buf))?;
+    out[..buf.len() - 8].copy_from_slice(&buf[8..]);
+    Ok(buf.len() - 8)
+}
+
+/// Wrap key with pad (RFC5649) into `out`, returning the wrapped length
+pub fn wrap_key_with_pad_into(
+    kek: &[u8],
+    plaintext: &[u8],
+    out: &mut [u8],
+) -> Result<usize, KeyWrapError> {
+    check_kek_len(kek)?;
+    check_kwp_plaintext_len(plaintext.len())?;
+    check_out_len(out, wrapped_len_with_pad(plaintext.len()))?;
+
+    out[8..8 + plaintext.len()].copy_from_slice(plaintext);
+    wrap_key_with_pad_in_place(kek, out, plaintext.len())
+}
+
+/// Unwrap key with pad (RFC5649) into `out`, returning the key length
+pub fn unwrap_key_with_pad_into(
+    kek: &[u8],
+    wrapped: &[u8],
+    out: &mut [u8],
+) -> Result<usize, KeyWrapError> {
+    check_kwp_wrapped_len(wrapped.len())?;
+    check_out_len(out, unwrapped_len_max(wrapped.len()))?;
+
+    let (buf, key_len) = unwrap_kwp_checked(wrapped, |buf| unwrap_in_place(kek, buf))?;
+    out[..key_len].copy_from_slice(&buf[8..8 + key_len]);
+    Ok(key_len)
+}
+
+// Opaque unwrap
+//
+// Every malformed or tampered input is reported as `UnwrapFailed`. The ICV,
+// MLI and padding checks are folded into one accumulator without early
+// exits, so a failing blob costs the same W^-1 and the same comparisons
+// whichever check it trips. Length errors return before W^-1, but the
+// length is chosen by the caller and reveals nothing about the key. A bad
+// KEK is a configuration error and is still reported as such.
+
+fn check_kek_len(kek: &[u8]) -> Result<(), KeyWrapError> {
+    match kek.len() {
+        16 | 24 | 32 => Ok(()),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+/// Unwrap key (RFC3394) without revealing why a failing input was rejected
+pub fn unwrap_key_opaque(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    check_kek_len(kek)?;
+    if check_kw_wrapped_len(wrapped.len()).is_err() {
+        return Err(KeyWrapError::UnwrapFailed);
+    }
+
+    let mut buf = locked_copy(wrapped)?;
+    unwrap_in_place(kek, &mut buf)?;
+    if !bool::from(buf[..8].ct_eq(&IV_3394[..])) {
+        return Err(KeyWrapError::UnwrapFailed);
+    }
+
+    UnwrappedKey::from_slice(&buf[8..])
+}
+
+/// Unwrap key with pad (RFC5649) without revealing why a failing input was
+/// rejected
+pub fn unwrap_key_with_pad_opaque(
+    kek: &[u8],
+    wrapped: &[u8],
+) -> Result<UnwrappedKey, KeyWrapError> {
+    check_kek_len(kek)?;
+    if check_kwp_wrapped_len(wrapped.len()).is_err() {
+        return Err(KeyWrapError::UnwrapFailed);
+    }
+
+    let mut buf = locked_copy(wrapped)?;
+    unwrap_in_place(kek, &mut buf)?;
+
+    let padded_len = wrapped.len() - 8;
+    let key_len = u32_from_be_u8(&to_u8_4_array(&buf[4..8])) as usize;
+    let mut valid = buf[..4].ct_eq(&IV_5649[..]);
+    valid &= Choice::from(((key_len > padded_len - 8) & (key_len <= padded_len)) as u8);
+    // padding can only sit in the last semiblock; scan all of it and mask
+    // off the octets that belong to the key
+    for pos in padded_len - 8..padded_len {
+        let is_padding = Choice::from((pos >= key_len) as u8);
+        valid &= !is_padding | buf[8 + pos].ct_eq(&0);
+    }
+    if !bool::from(valid) {
+        return Err(KeyWrapError::UnwrapFailed);
+    }
+
+    UnwrappedKey::from_slice(&buf[8..8 + key_len])
 }
 
 macro_rules! impl_aes_keywrap {
     ($name: tt, $cipher:tt) => {
-        pub struct $name {}
+        /// Key wrap context holding the expanded KEK
+        ///
+        /// `new` runs the AES key schedule once; `wrap`, `unwrap`,
+        /// `wrap_with_pad` and `unwrap_with_pad` reuse it, so services
+        /// wrapping many keys under one KEK don't expand it on every call.
+        /// The associated `aes_*` functions take the raw KEK instead.
+        pub struct $name {
+            cipher: $cipher,
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), "(..redacted..)"))
+            }
+        }
 
         impl $name {
-            pub fn aes_unwrap_key_and_iv(kek: &[u8], wrapped: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
-                let n = wrapped.len() / 8 - 1;
-                let mut r: Vec<[u8; 8]> = Vec::new();
-                r.push([0u8; 8]);
-                for i in 1..n + 1 {
-                    r.push(to_u8_8_array(&wrapped[i * 8..i * 8 + 8]));
-                }
+            pub fn new(kek: &[u8]) -> Result<$name, KeyWrapError> {
+                Self::check_kek(kek)?;
+                Ok($name {
+                    cipher: $cipher::try_new(kek)?,
+                })
+            }
 
-                let mut a = u64_from_be_u8(&to_u8_8_array(&wrapped[..8]));
-                let mut cipher = $cipher::new(kek);
-
-                for j in (0..6).rev() {
-                    for i in (1..n + 1).rev() {
-                        let mut ciphertext: Vec<u8> = Vec::new();
-                        ciphertext
-                            .write(&(a ^ (n * j + i) as u64).to_be_bytes())
-                            .unwrap();
-                        ciphertext.write(&r[i]).unwrap();
-                        cipher.decrypt(&mut ciphertext);
-                        a = u64_from_be_u8(&to_u8_8_array(&ciphertext[..8]));
-                        r[i].copy_from_slice(&ciphertext[8..]);
-                    }
+            // the backends assert the key length, so check it before them
+            fn check_kek(kek: &[u8]) -> Result<(), KeyWrapError> {
+                if kek.len() != $cipher::KEY_LEN {
+                    return Err(KeyWrapError::InvalidKekLength(kek.len()));
                 }
+                Ok(())
+            }
+
+            // each call works on its own copy of the key schedule, so a
+            // context can be shared between threads
+            fn cipher(&self) -> Result<$cipher, KeyWrapError> {
+                self_test::power_up()?;
+                AesEcb::try_clone(&self.cipher)
+            }
+
+            /// Wrap key with the IV defined in RFC3394
+            pub fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+                kw_wrap_with(plaintext, |buf| Self::w(&mut self.cipher()?, buf))
+            }
+
+            /// Unwrap key and Check IV in RFC3394
+            pub fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+                let buf = unwrap_kw_checked(wrapped, |buf| Self::w_inv(&mut self.cipher()?, buf))?;
+                UnwrappedKey::from_slice(&buf[8..])
+            }
+
+            /// Wrap key with pad using padding algorithm (RFC5649)
+            pub fn wrap_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+                kwp_wrap_with(plaintext, |buf| Self::w(&mut self.cipher()?, buf))
+            }
+
+            /// Unwrap key with pad using padding algorithm (RFC5649)
+            pub fn unwrap_with_pad(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+                let (buf, key_len) =
+                    unwrap_kwp_checked(wrapped, |buf| Self::w_inv(&mut self.cipher()?, buf))?;
+                UnwrappedKey::from_slice(&buf[8..8 + key_len])
+            }
 
-                let mut key: Vec<u8> = Vec::new();
-                for v in &r[1..] {
-                    key.write(v).unwrap();
+            /// Unwrap a batch of keys (RFC3394), reporting each item's
+            /// outcome separately so one corrupted blob doesn't stop the
+            /// others from loading
+            pub fn unwrap_many<T: AsRef<[u8]>>(
+                &self,
+                wrapped: &[T],
+            ) -> Vec<Result<UnwrappedKey, KeyWrapError>> {
+                wrapped.iter().map(|w| self.unwrap(w.as_ref())).collect()
+            }
+
+            fn unwrap_key_and_iv(kek: &[u8], wrapped: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+                // RFC3394: at least two semiblocks of key data plus the ICV
+                check_kw_wrapped_len(wrapped.len())?;
+
+                let mut buf = Zeroizing::new(wrapped.to_vec());
+                Self::unwrap_in_place(kek, &mut buf)?;
+                Ok((buf[8..].to_vec(), buf[..8].to_vec()))
+            }
+
+            fn wrap_key_and_iv(kek: &[u8], plaintext: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
+                check_kw_plaintext_len(plaintext.len()).map_err(|e| match e {
+                    KeyWrapError::InvalidPlaintextLength(_) => format!(
+                        "{} (RFC3394 needs a multiple of 8 and at least 16 octets, \
+                         use aes_wrap_key_with_pad for other lengths)",
+                        e
+                    ),
+                    e => e.to_string(),
+                })?;
+                if iv.len() != 8 {
+                    return Err(format!("iv must be 8 octets, got {}", iv.len()));
                 }
 
-                Ok((key, a.to_be_bytes().to_vec()))
+                let mut ret: Vec<u8> = Vec::with_capacity(plaintext.len() + 8);
+                ret.extend_from_slice(iv);
+                ret.extend_from_slice(plaintext);
+                Self::wrap_in_place(kek, &mut ret)?;
+                Ok(ret)
             }
 
-            pub fn aes_wrap_key_and_iv(kek: &[u8], plaintext: &[u8], iv: &[u8]) -> Result<Vec<u8>, String> {
-                let n = plaintext.len() / 8;
-                let mut r: Vec<[u8; 8]> = Vec::new();
-                r.push([0u8; 8]);
-                for i in 0..n {
-                    r.push(to_u8_8_array(&plaintext[i * 8..i * 8 + 8]));
+            pub fn aes_unwrap_key_and_iv(
+                kek: &[u8],
+                wrapped: &[u8],
+            ) -> Result<(Vec<u8>, Vec<u8>), String> {
+                self_test::power_up()?;
+                Self::check_kek(kek)?;
+                Self::unwrap_key_and_iv(kek, wrapped)
+            }
+
+            pub fn aes_wrap_key_and_iv(
+                kek: &[u8],
+                plaintext: &[u8],
+                iv: &[u8],
+            ) -> Result<Vec<u8>, String> {
+                self_test::power_up()?;
+                Self::check_kek(kek)?;
+                Self::wrap_key_and_iv(kek, plaintext, iv)
+            }
+
+            pub fn aes_unwrap_key_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+                self_test::power_up()?;
+                Self::check_kek(kek)?;
+                Self::unwrap_key_with_pad(kek, wrapped)
+            }
+
+            pub fn aes_wrap_key_with_pad(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+                self_test::power_up()?;
+                Self::check_kek(kek)?;
+                Self::wrap_key_with_pad(kek, plaintext)
+            }
+
+            // The `aes_*` bodies without the self-test gate, for the free
+            // functions (which gate once themselves) and the KATs
+
+            // W over `buf` = A | R[1] | ... | R[n], in place. With a single
+            // semiblock (RFC5649 short case) it is one plain ECB encryption.
+            fn wrap_in_place(kek: &[u8], buf: &mut [u8]) -> Result<(), KeyWrapError> {
+                Self::w(&mut $cipher::try_new(kek)?, buf)
+            }
+
+            // W^-1, the inverse of `wrap_in_place`
+            fn unwrap_in_place(kek: &[u8], buf: &mut [u8]) -> Result<(), KeyWrapError> {
+                Self::w_inv(&mut $cipher::try_new(kek)?, buf)
+            }
+
+            // a failing backend leaves `buf` half processed, so wipe it
+            fn w(cipher: &mut $cipher, buf: &mut [u8]) -> Result<(), KeyWrapError> {
+                block_cipher::w(cipher, buf).inspect_err(|_| buf.zeroize())
+            }
+
+            fn w_inv(cipher: &mut $cipher, buf: &mut [u8]) -> Result<(), KeyWrapError> {
+                block_cipher::w_inv(cipher, buf).inspect_err(|_| buf.zeroize())
+            }
+
+            // W over equal-length buffers, `multilane::LANES` at a time in
+            // lockstep: each step encrypts one block per lane in a single
+            // ECB call, so the lanes' AES rounds overlap in the pipeline
+            fn w_lanes(kek: &[u8], bufs: &mut [&mut [u8]]) -> Result<(), KeyWrapError> {
+                let n = bufs[0].len() / 8 - 1;
+                let mut cipher = $cipher::try_new(kek)?;
+                let mut blocks = Zeroizing::new([0u8; 16 * multilane::LANES]);
+                let result = Self::w_lanes_with(&mut cipher, n, bufs, &mut blocks[..]);
+                if result.is_err() {
+                    for buf in bufs.iter_mut() {
+                        buf.zeroize();
+                    }
                 }
+                result
+            }
 
-                let mut a = u64_from_be_u8(&to_u8_8_array(&iv[..8]));
-                let mut cipher = $cipher::new(kek);
-
-                for j in 0..6 {
-                    for i in 1..n + 1 {
-                        let mut ciphertext: Vec<u8> = Vec::new();
-                        ciphertext.write(&a.to_be_bytes()).unwrap();
-                        ciphertext.write(&r[i]).unwrap();
-                        cipher.encrypt(&mut ciphertext);
-                        a = u64_from_be_u8(&to_u8_8_array(&ciphertext[..8])) ^ (n * j + i) as u64;
-                        r[i].copy_from_slice(&ciphertext[8..]);
+            fn w_lanes_with(
+                cipher: &mut $cipher,
+                n: usize,
+                bufs: &mut [&mut [u8]],
+                blocks: &mut [u8],
+            ) -> Result<(), KeyWrapError> {
+                for lanes in bufs.chunks_mut(multilane::LANES) {
+                    let b = &mut blocks[..16 * lanes.len()];
+                    if n == 1 {
+                        for (l, buf) in lanes.iter().enumerate() {
+                            b[l * 16..l * 16 + 16].copy_from_slice(buf);
+                        }
+                        cipher.try_encrypt(b)?;
+                        for (l, buf) in lanes.iter_mut().enumerate() {
+                            buf.copy_from_slice(&b[l * 16..l * 16 + 16]);
+                        }
+                        continue;
+                    }
+
+                    for j in 0..6 {
+                        for i in 1..n + 1 {
+                            for (l, buf) in lanes.iter().enumerate() {
+                                b[l * 16..l * 16 + 8].copy_from_slice(&buf[..8]);
+                                b[l * 16 + 8..l * 16 + 16].copy_from_slice(&buf[i * 8..i * 8 + 8]);
+                            }
+                            cipher.try_encrypt(b)?;
+                            let t = ((n * j + i) as u64).to_be_bytes();
+                            for (l, buf) in lanes.iter_mut().enumerate() {
+                                for k in 0..8 {
+                                    buf[k] = b[l * 16 + k] ^ t[k];
+                                }
+                                buf[i * 8..i * 8 + 8].copy_from_slice(&b[l * 16 + 8..l * 16 + 16]);
+                            }
+                        }
                     }
                 }
+                Ok(())
+            }
 
-                let mut ret: Vec<u8> = Vec::new();
-                ret.write(&a.to_be_bytes()).unwrap();
-                for v in &r[1..] {
-                    ret.write(v).unwrap();
+            // W^-1 counterpart of `w_lanes`
+            fn w_inv_lanes(kek: &[u8], bufs: &mut [&mut [u8]]) -> Result<(), KeyWrapError> {
+                let n = bufs[0].len() / 8 - 1;
+                let mut cipher = $cipher::try_new(kek)?;
+                let mut blocks = Zeroizing::new([0u8; 16 * multilane::LANES]);
+                let result = Self::w_inv_lanes_with(&mut cipher, n, bufs, &mut blocks[..]);
+                if result.is_err() {
+                    for buf in bufs.iter_mut() {
+                        buf.zeroize();
+                    }
                 }
+                result
+            }
 
-                Ok(ret)
+            fn w_inv_lanes_with(
+                cipher: &mut $cipher,
+                n: usize,
+                bufs: &mut [&mut [u8]],
+                blocks: &mut [u8],
+            ) -> Result<(), KeyWrapError> {
+                for lanes in bufs.chunks_mut(multilane::LANES) {
+                    let b = &mut blocks[..16 * lanes.len()];
+                    if n == 1 {
+                        for (l, buf) in lanes.iter().enumerate() {
+                            b[l * 16..l * 16 + 16].copy_from_slice(buf);
+                        }
+                        cipher.try_decrypt(b)?;
+                        for (l, buf) in lanes.iter_mut().enumerate() {
+                            buf.copy_from_slice(&b[l * 16..l * 16 + 16]);
+                        }
+                        continue;
+                    }
+
+                    for j in (0..6).rev() {
+                        for i in (1..n + 1).rev() {
+                            let t = ((n * j + i) as u64).to_be_bytes();
+                            for (l, buf) in lanes.iter().enumerate() {
+                                for k in 0..8 {
+                                    b[l * 16 + k] = buf[k] ^ t[k];
+                                }
+                                b[l * 16 + 8..l * 16 + 16].copy_from_slice(&buf[i * 8..i * 8 + 8]);
+                            }
+                            cipher.try_decrypt(b)?;
+                            for (l, buf) in lanes.iter_mut().enumerate() {
+                                buf[..8].copy_from_slice(&b[l * 16..l * 16 + 8]);
+                                buf[i * 8..i * 8 + 8].copy_from_slice(&b[l * 16 + 8..l * 16 + 16]);
+                            }
+                        }
+                    }
+                }
+                Ok(())
             }
 
-            pub fn aes_unwrap_key_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
-                let mut key: Vec<u8> = Vec::new();
+            fn unwrap_key_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+                // RFC5649: a single semiblock is wrapped as one AES block
+                check_kwp_wrapped_len(wrapped.len())?;
+                let mut key = Zeroizing::new(Vec::new());
                 let mut key_iv: Vec<u8> = Vec::new();
                 if wrapped.len() == 16 {
-                    let mut cipher = $cipher::new(kek);
-                    let mut plaintext: Vec<u8> = Vec::new();
-                    plaintext.write(wrapped).unwrap();
-                    cipher.decrypt(&mut plaintext);
-                    key_iv.write(&plaintext[..8]).unwrap();
-                    key.write(&plaintext[8..]).unwrap();
+                    let mut cipher = $cipher::try_new(kek)?;
+                    let mut plaintext = Zeroizing::new(wrapped.to_vec());
+                    cipher.try_decrypt(&mut plaintext)?;
+                    key_iv.extend_from_slice(&plaintext[..8]);
+                    key.extend_from_slice(&plaintext[8..]);
                 } else {
-                    let (_key, _key_iv) = aes_unwrap_key_and_iv(kek, wrapped)?;
-                    key.write(&_key).unwrap();
-                    key_iv.write(&_key_iv).unwrap();
+                    let (_key, _key_iv) = Self::unwrap_key_and_iv(kek, wrapped)?;
+                    let _key = Zeroizing::new(_key);
+                    key.extend_from_slice(&_key);
+                    key_iv.extend_from_slice(&_key_iv);
                 }
 
-                if IV_5649 != to_u8_4_array(&key_iv[..4]) {
+                if !bool::from(key_iv[..4].ct_eq(&IV_5649[..])) {
                     return Err(format!(
                         "IV Check Failed: {:?} (expected A65959A6)",
-                        to_u8_4_array(&key_iv[..4]))
-                    );
+                        to_u8_4_array(&key_iv[..4])
+                    ));
                 }
 
                 //RFC5649: 32bit fixed + 32bit length
-                let key_len: usize = u32_from_be_u8(&to_u8_4_array(&key_iv[4..])) as usize;
+                let mli = u32_from_be_u8(&to_u8_4_array(&key_iv[4..]));
+                let key_len = mli as usize;
+                if key_len <= key.len() - 8 || key_len > key.len() {
+                    return Err(KeyWrapError::InvalidMli(mli).into());
+                }
+                if key_len < key.len() && key[key_len..].iter().any(|b| *b != 0) {
+                    return Err(KeyWrapError::InvalidPadding.into());
+                }
                 Ok(key[..key_len].to_vec())
             }
 
-            pub fn aes_wrap_key_with_pad(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+            fn wrap_key_with_pad(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+                check_kwp_plaintext_len(plaintext.len())?;
                 let mut iv: Vec<u8> = Vec::new();
                 //RFC5649: 32bit fixed + 32bit length
-                iv.write(&IV_5649).unwrap();
-                iv.write(&(plaintext.len() as u32).to_be_bytes()).unwrap();
+                iv.extend_from_slice(&IV_5649);
+                iv.extend_from_slice(&(plaintext.len() as u32).to_be_bytes());
 
-                let mut pad_pt: Vec<u8> = Vec::new();
-                pad_pt.write(plaintext).unwrap();
-                let n = ((8 - plaintext.len() as i32) % 8).abs() as usize;
+                let mut pad_pt = Zeroizing::new(Vec::with_capacity(plaintext.len() + 7));
+                pad_pt.extend_from_slice(plaintext);
+                let n = (8 - plaintext.len() % 8) % 8;
                 for _ in 0..n {
                     pad_pt.push(0u8);
                 }
 
                 if pad_pt.len() == 8 {
-                    let mut cipher = $cipher::new(kek);
+                    let mut cipher = $cipher::try_new(kek)?;
                     let mut wrapped: Vec<u8> = Vec::new();
-                    wrapped.write(&iv).unwrap();
-                    wrapped.write(&pad_pt).unwrap();
-                    cipher.encrypt(&mut wrapped);
+                    wrapped.extend_from_slice(&iv);
+                    wrapped.extend_from_slice(&pad_pt);
+                    cipher.try_encrypt(&mut wrapped)?;
                     Ok(wrapped.to_vec())
                 } else {
-                    aes_wrap_key_and_iv(kek, &pad_pt, &iv)
+                    Self::wrap_key_and_iv(kek, &pad_pt, &iv)
                 }
             }
         }
@@ -237,8 +1183,10 @@ impl_aes_keywrap!(Aes256Kw, Aes256Ecb);
 #[cfg(test)]
 mod tests {
     use super::*;
-    use hex;
-    use test::Bencher;
+
+    fn exposed(key: Result<UnwrappedKey, KeyWrapError>) -> Result<Vec<u8>, KeyWrapError> {
+        key.map(|k| k.expose_secret().to_vec())
+    }
 
     // RFC3394 tests
     #[test]
@@ -327,53 +1275,522 @@ mod tests {
         assert_eq!(plain, aes_unwrap_key_with_pad(&kek, &cipher).unwrap());
     }
 
-    #[bench]
-    fn bench_128bit_key_wrap(b: &mut Bencher) {
-        b.iter(|| {
-            for _ in 0..100 {
-                let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
-                let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
-                aes_wrap_key(&kek, &plain);
-            }
-        });
+    #[test]
+    fn test_padded_two_semiblocks_roundtrip() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        for len in 9..17 {
+            let plain: Vec<u8> = (0..len as u8).collect();
+            let cipher = aes_wrap_key_with_pad(&kek, &plain).unwrap();
+            assert_eq!(24, cipher.len());
+            assert_eq!(plain, aes_unwrap_key_with_pad(&kek, &cipher).unwrap());
+        }
     }
 
-    #[bench]
-    fn bench_128bit_key_unwrap(b: &mut Bencher) {
-        b.iter(|| {
-            for _ in 0..100 {
-                let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
-                let cipher =
-                    hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
-                aes_unwrap_key(&kek, &cipher);
+    #[test]
+    fn test_padded_custom_aiv() {
+        let kek = hex::decode("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8").unwrap();
+        let key = hex::decode("c37b7e6492584340bed12207808941155068f738").unwrap();
+        assert_eq!(
+            aes_wrap_key_with_pad(&kek, &key).unwrap(),
+            aes_wrap_key_with_pad_and_aiv(&kek, &key, &IV_5649).unwrap()
+        );
+
+        let aiv = [0x01, 0x02, 0x03, 0x04];
+        for key in [&key[..], &key[..7]].iter() {
+            let wrapped = aes_wrap_key_with_pad_and_aiv(&kek, key, &aiv).unwrap();
+            assert_eq!(
+                *key,
+                aes_unwrap_key_with_pad_and_aiv(&kek, &wrapped, &aiv)
+                    .unwrap()
+                    .expose_secret()
+            );
+            assert_eq!(
+                Err(KeyWrapError::IntegrityCheckFailed),
+                exposed(try_unwrap_key_with_pad_and_aiv(&kek, &wrapped, &IV_5649))
+            );
+            assert!(aes_unwrap_key_with_pad(&kek, &wrapped).is_err());
+        }
+        assert_eq!(
+            Err(KeyWrapError::InvalidKekLength(20)),
+            try_wrap_key_with_pad_and_aiv(&kek[..20], &key, &aiv)
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidKekLength(20).to_string()),
+            aes_unwrap_key_with_pad_and_aiv(&kek[..20], &[0u8; 24], &aiv).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_wrap_rejects_unaligned_plaintext() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF0001020304050607").unwrap();
+        for len in [0, 8, 15, 17, 23].iter() {
+            let err = aes_wrap_key(&kek, &plain[..*len]).unwrap_err();
+            assert!(err.contains("aes_wrap_key_with_pad"), "{}", err);
+        }
+        assert!(aes_wrap_key(&kek, &plain).is_ok());
+    }
+
+    #[test]
+    fn test_unwrap_rejects_invalid_wrapped_length() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        for len in [0, 7, 8, 16, 23].iter() {
+            assert_eq!(
+                Err(format!("Invalid wrapped length: {}", len)),
+                aes_unwrap_key(&kek, &cipher[..*len])
+            );
+        }
+        for len in [0, 8, 15, 17, 23].iter() {
+            assert!(aes_unwrap_key_with_pad(&kek, &cipher[..*len]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_unsupported_kek_is_not_printed() {
+        let kek = [0xA5u8; 20];
+        let expected = Err(String::from(
+            "kek is not supported: 20 bytes (expected 16, 24 or 32)",
+        ));
+        let plain = [0u8; 16];
+        assert_eq!(expected, aes_wrap_key(&kek, &plain));
+        assert_eq!(expected, aes_wrap_key_with_pad(&kek, &plain));
+        assert_eq!(expected, aes_unwrap_key_with_pad(&kek, &[0u8; 24]));
+        assert_eq!(
+            expected.map(|v| (v, Vec::new())),
+            aes_unwrap_key_and_iv(&kek, &[0u8; 24])
+        );
+    }
+
+    #[test]
+    fn test_kw_type_rejects_mismatched_kek() {
+        let expected = Err(String::from(KeyWrapError::InvalidKekLength(32)));
+        let (kek, plain) = ([0u8; 32], [0u8; 16]);
+        assert_eq!(
+            expected,
+            Aes128Kw::aes_wrap_key_and_iv(&kek, &plain, &[0xa6; 8])
+        );
+        assert_eq!(expected, Aes128Kw::aes_wrap_key_with_pad(&kek, &plain));
+        assert_eq!(
+            expected,
+            Aes192Kw::aes_unwrap_key_with_pad(&kek, &[0u8; 16])
+        );
+        assert_eq!(
+            expected.map(|v| (v, Vec::new())),
+            Aes192Kw::aes_unwrap_key_and_iv(&kek, &[0u8; 24])
+        );
+        assert_eq!(
+            Err(String::from(KeyWrapError::InvalidKekLength(16))),
+            Aes256Kw::aes_wrap_key_with_pad(&[0u8; 16], &plain)
+        );
+    }
+
+    #[test]
+    fn test_padded_unwrap_rejects_nonzero_padding() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        for wrapped in [
+            "138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A",
+            "AFBEB0F07DFBF5419200F2CCB50BB24F",
+        ]
+        .iter()
+        {
+            let wrapped = hex::decode(wrapped).unwrap();
+            let vectors = kwp_negative_vectors(&kek, &wrapped).unwrap();
+            let v = vectors
+                .iter()
+                .find(|v| v.tamper == Tamper::NonZeroPadding)
+                .unwrap();
+            assert_eq!(
+                Err(String::from("Invalid padding")),
+                aes_unwrap_key_with_pad(&kek, &v.wrapped)
+            );
+        }
+    }
+
+    #[test]
+    fn test_padded_unwrap_rejects_out_of_range_mli() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let wrapped =
+            hex::decode("138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A")
+                .unwrap();
+        for v in kwp_negative_vectors(&kek, &wrapped).unwrap() {
+            if let Tamper::ModifiedMli { mli } = v.tamper {
+                assert_eq!(
+                    Err(format!("Invalid message length indicator: {}", mli)),
+                    aes_unwrap_key_with_pad(&kek, &v.wrapped)
+                );
             }
-        });
-    }
-
-    #[bench]
-    fn bench_15628bit_key_wrap(b: &mut Bencher) {
-        b.iter(|| {
-            for _ in 0..100 {
-                let kek =
-                    hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
-                        .unwrap();
-                let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
-                aes_wrap_key(&kek, &plain);
+        }
+    }
+
+    #[test]
+    fn test_sp800_38f_length_limits() {
+        assert_eq!(
+            Err(KeyWrapError::InvalidPlaintextLength(0)),
+            check_kwp_plaintext_len(0)
+        );
+        assert!(aes_wrap_key_with_pad(&[0u8; 16], &[]).is_err());
+        assert_eq!(Ok(()), check_kwp_plaintext_len(u32::MAX as usize));
+        if cfg!(target_pointer_width = "64") {
+            assert_eq!(Ok(()), check_kwp_wrapped_len(u32::MAX as usize + 9));
+            let too_long = u32::MAX as usize + 1;
+            assert_eq!(
+                Err(KeyWrapError::LengthOverflow(too_long)),
+                check_kwp_plaintext_len(too_long)
+            );
+            assert!(check_kwp_wrapped_len(u32::MAX as usize + 17).is_err());
+
+            let semiblocks = (KW_MAX_SEMIBLOCKS + 1) as usize;
+            assert_eq!(Ok(()), check_kw_plaintext_len((semiblocks - 1) * 8));
+            assert_eq!(
+                Err(KeyWrapError::LengthOverflow(semiblocks * 8)),
+                check_kw_plaintext_len(semiblocks * 8)
+            );
+            assert_eq!(Ok(()), check_kw_wrapped_len(semiblocks * 8));
+            assert!(check_kw_wrapped_len((semiblocks + 1) * 8).is_err());
+        }
+    }
+
+    #[test]
+    fn test_opaque_unwrap() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapped = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        assert_eq!(
+            exposed(try_unwrap_key(&kek, &wrapped)),
+            exposed(unwrap_key_opaque(&kek, &wrapped))
+        );
+        for v in kw_negative_vectors(&kek, &wrapped).unwrap() {
+            assert_eq!(
+                Err(KeyWrapError::UnwrapFailed),
+                exposed(unwrap_key_opaque(&kek, &v.wrapped))
+            );
+        }
+        assert_eq!(
+            Err(KeyWrapError::InvalidKekLength(15)),
+            exposed(unwrap_key_opaque(&kek[..15], &wrapped))
+        );
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        for wrapped in [
+            "138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A",
+            "AFBEB0F07DFBF5419200F2CCB50BB24F",
+        ]
+        .iter()
+        {
+            let wrapped = hex::decode(wrapped).unwrap();
+            assert_eq!(
+                exposed(try_unwrap_key_with_pad(&kek, &wrapped)),
+                exposed(unwrap_key_with_pad_opaque(&kek, &wrapped))
+            );
+            for v in kwp_negative_vectors(&kek, &wrapped).unwrap() {
+                assert_eq!(
+                    Err(KeyWrapError::UnwrapFailed),
+                    exposed(unwrap_key_with_pad_opaque(&kek, &v.wrapped)),
+                    "{:?}",
+                    v.tamper
+                );
             }
-        });
-    }
-
-    #[bench]
-    fn bench_256bit_key_unwrap(b: &mut Bencher) {
-        b.iter(|| {
-            for _ in 0..100 {
-                let kek =
-                    hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
-                        .unwrap();
-                let cipher =
-                    hex::decode("64E8C3F9CE0F5BA263E9777905818A2A93C8191E7D6E8AE7").unwrap();
-                aes_unwrap_key(&kek, &cipher);
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_unwrap_keys_reports_each_item() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let good = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let mut bad = good.clone();
+        bad[10] ^= 1;
+        let results = aes_unwrap_keys(&kek, &[&good, &bad, &good]);
+        assert_eq!(3, results.len());
+        assert_eq!(plain, results[0].clone().unwrap());
+        assert!(results[1].is_err());
+        assert_eq!(plain, results[2].clone().unwrap());
+    }
+
+    #[test]
+    fn test_try_api() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        assert_eq!(cipher, try_wrap_key(&kek, &plain).unwrap());
+        assert_eq!(plain, exposed(try_unwrap_key(&kek, &cipher)).unwrap());
+
+        let mut tampered = cipher.clone();
+        tampered[10] ^= 1;
+        assert_eq!(
+            Err(KeyWrapError::IntegrityCheckFailed),
+            exposed(try_unwrap_key(&kek, &tampered))
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidCiphertextLength(16)),
+            exposed(try_unwrap_key(&kek, &cipher[..16]))
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidPlaintextLength(12)),
+            try_wrap_key(&kek, &plain[..12])
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidKekLength(15)),
+            exposed(try_unwrap_key(&kek[..15], &cipher))
+        );
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        for (plain, cipher) in [
+            (
+                "C37B7E6492584340BED12207808941155068F738",
+                "138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A",
+            ),
+            ("466F7250617369", "AFBEB0F07DFBF5419200F2CCB50BB24F"),
+        ]
+        .iter()
+        {
+            let plain = hex::decode(plain).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(cipher, try_wrap_key_with_pad(&kek, &plain).unwrap());
+            assert_eq!(
+                plain,
+                exposed(try_unwrap_key_with_pad(&kek, &cipher)).unwrap()
+            );
+        }
+        assert_eq!(
+            Err(KeyWrapError::InvalidCiphertextLength(8)),
+            exposed(try_unwrap_key_with_pad(&kek, &[0u8; 8]))
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidPlaintextLength(0)),
+            try_wrap_key_with_pad(&kek, &[])
+        );
+    }
+
+    #[test]
+    fn test_try_api_rejects_negative_vectors() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapped = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        for v in kw_negative_vectors(&kek, &wrapped).unwrap() {
+            assert!(
+                exposed(try_unwrap_key(&kek, &v.wrapped)).is_err(),
+                "{:?}",
+                v.tamper
+            );
+        }
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let wrapped =
+            hex::decode("138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A")
+                .unwrap();
+        for v in kwp_negative_vectors(&kek, &wrapped).unwrap() {
+            let err = exposed(try_unwrap_key_with_pad(&kek, &v.wrapped)).unwrap_err();
+            match v.expected {
+                ExpectedFailure::InvalidPadding => assert_eq!(KeyWrapError::InvalidPadding, err),
+                ExpectedFailure::InvalidMli => {
+                    assert!(matches!(err, KeyWrapError::InvalidMli(_)), "{:?}", v.tamper)
+                }
+                ExpectedFailure::InvalidLength => {
+                    assert_eq!(KeyWrapError::InvalidCiphertextLength(v.wrapped.len()), err)
+                }
+                _ => assert_eq!(KeyWrapError::IntegrityCheckFailed, err, "{:?}", v.tamper),
             }
-        });
+        }
+    }
+
+    #[test]
+    fn test_verify_wrapped() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapped = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        assert_eq!(Ok(()), aes_verify_wrapped(&kek, &wrapped));
+        for v in kw_negative_vectors(&kek, &wrapped).unwrap() {
+            assert_eq!(
+                exposed(try_unwrap_key(&kek, &v.wrapped)).map(|_| ()),
+                aes_verify_wrapped(&kek, &v.wrapped)
+            );
+        }
+        assert_eq!(
+            Err(KeyWrapError::InvalidKekLength(15)),
+            aes_verify_wrapped(&kek[..15], &wrapped)
+        );
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let wrapped = hex::decode("AFBEB0F07DFBF5419200F2CCB50BB24F").unwrap();
+        assert_eq!(Ok(()), aes_verify_wrapped_with_pad(&kek, &wrapped));
+        for v in kwp_negative_vectors(&kek, &wrapped).unwrap() {
+            assert_eq!(
+                exposed(try_unwrap_key_with_pad(&kek, &v.wrapped)).map(|_| ()),
+                aes_verify_wrapped_with_pad(&kek, &v.wrapped),
+                "{:?}",
+                v.tamper
+            );
+        }
+    }
+
+    #[test]
+    fn test_w_primitive() {
+        // RFC 3394 4.1 is W over IV | P
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let mut s = [[0u8; 8]; 3];
+        s[0] = IV_3394;
+        s[1..].as_flattened_mut().copy_from_slice(&plain);
+        w(&kek, &mut s).unwrap();
+        assert_eq!(
+            hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap(),
+            s.as_flattened()
+        );
+        w_inverse(&kek, &mut s).unwrap();
+        assert_eq!(IV_3394, s[0]);
+        assert_eq!(&plain[..], s[1..].as_flattened());
+
+        assert_eq!(
+            Err(KeyWrapError::InvalidPlaintextLength(16)),
+            w(&kek, &mut s[..2])
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidCiphertextLength(16)),
+            w_inverse(&kek, &mut s[..2])
+        );
+        assert_eq!(Err(KeyWrapError::InvalidKekLength(8)), w(&kek[..8], &mut s));
+    }
+
+    #[test]
+    fn test_in_place_api() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let mut buf = [0u8; 24];
+        buf[8..].copy_from_slice(&plain);
+        assert_eq!(Ok(24), wrap_key_in_place(&kek, &mut buf));
+        assert_eq!(&cipher[..], &buf[..]);
+        assert_eq!(Ok(&plain[..]), unwrap_key_in_place(&kek, &mut buf));
+
+        buf.copy_from_slice(&cipher);
+        buf[10] ^= 1;
+        assert_eq!(
+            Err(KeyWrapError::IntegrityCheckFailed),
+            unwrap_key_in_place(&kek, &mut buf)
+        );
+        assert_eq!([0u8; 24], buf);
+        assert_eq!(
+            Err(KeyWrapError::InvalidPlaintextLength(8)),
+            wrap_key_in_place(&kek, &mut buf[..16])
+        );
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        for (plain, cipher) in [
+            (
+                "C37B7E6492584340BED12207808941155068F738",
+                "138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A",
+            ),
+            ("466F7250617369", "AFBEB0F07DFBF5419200F2CCB50BB24F"),
+        ]
+        .iter()
+        {
+            let plain = hex::decode(plain).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(cipher.len(), wrapped_len_with_pad(plain.len()));
+            let mut buf = vec![0xffu8; cipher.len() + 4];
+            buf[8..8 + plain.len()].copy_from_slice(&plain);
+            assert_eq!(
+                Ok(cipher.len()),
+                wrap_key_with_pad_in_place(&kek, &mut buf, plain.len())
+            );
+            assert_eq!(&cipher[..], &buf[..cipher.len()]);
+            assert_eq!([0xffu8; 4], buf[cipher.len()..]);
+            assert_eq!(
+                Ok(&plain[..]),
+                unwrap_key_with_pad_in_place(&kek, &mut buf[..cipher.len()])
+            );
+        }
+        assert_eq!(
+            Err(KeyWrapError::BufferTooSmall(16)),
+            wrap_key_with_pad_in_place(&kek, &mut [0u8; 15], 7)
+        );
+    }
+
+    #[test]
+    fn test_into_api() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let mut out = [0u8; 32];
+        assert_eq!(24, wrapped_len(plain.len()));
+        assert_eq!(Ok(24), wrap_key_into(&kek, &plain, &mut out));
+        assert_eq!(&cipher[..], &out[..24]);
+        assert_eq!(16, unwrapped_len_max(cipher.len()));
+        assert_eq!(Ok(16), unwrap_key_into(&kek, &cipher, &mut out));
+        assert_eq!(&plain[..], &out[..16]);
+        assert_eq!(
+            Err(KeyWrapError::BufferTooSmall(24)),
+            wrap_key_into(&kek, &plain, &mut out[..23])
+        );
+        assert_eq!(
+            Err(KeyWrapError::BufferTooSmall(16)),
+            unwrap_key_into(&kek, &cipher, &mut out[..15])
+        );
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let cipher = hex::decode("AFBEB0F07DFBF5419200F2CCB50BB24F").unwrap();
+        let plain = hex::decode("466F7250617369").unwrap();
+        assert_eq!(Ok(16), wrap_key_with_pad_into(&kek, &plain, &mut out));
+        assert_eq!(&cipher[..], &out[..16]);
+        assert_eq!(
+            Ok(7),
+            unwrap_key_with_pad_into(&kek, &cipher, &mut out[..8])
+        );
+        assert_eq!(&plain[..], &out[..7]);
+        assert_eq!(
+            Err(KeyWrapError::BufferTooSmall(8)),
+            unwrap_key_with_pad_into(&kek, &cipher, &mut out[..7])
+        );
+    }
+
+    #[test]
+    fn test_kw_context() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let cipher = hex::decode("64E8C3F9CE0F5BA263E9777905818A2A93C8191E7D6E8AE7").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let kw = Aes256Kw::new(&kek).unwrap();
+        for _ in 0..2 {
+            assert_eq!(cipher, kw.wrap(&plain).unwrap());
+            assert_eq!(plain, exposed(kw.unwrap(&cipher)).unwrap());
+        }
+        let mut tampered = cipher.clone();
+        tampered[10] ^= 1;
+        assert_eq!(
+            Err(KeyWrapError::IntegrityCheckFailed),
+            exposed(kw.unwrap(&tampered))
+        );
+        let results = kw.unwrap_many(&[&cipher, &tampered]);
+        assert_eq!(&plain[..], results[0].as_ref().unwrap().expose_secret());
+        assert_eq!(
+            Some(KeyWrapError::IntegrityCheckFailed),
+            results[1].as_ref().err().copied()
+        );
+        assert_eq!("Aes256Kw(..redacted..)", format!("{:?}", kw));
+        assert_eq!(
+            Some(KeyWrapError::InvalidKekLength(32)),
+            Aes128Kw::new(&kek).err()
+        );
+
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let kw = Aes192Kw::new(&kek).unwrap();
+        for (plain, cipher) in [
+            (
+                "C37B7E6492584340BED12207808941155068F738",
+                "138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A",
+            ),
+            ("466F7250617369", "AFBEB0F07DFBF5419200F2CCB50BB24F"),
+        ]
+        .iter()
+        {
+            let plain = hex::decode(plain).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(cipher, kw.wrap_with_pad(&plain).unwrap());
+            assert_eq!(plain, exposed(kw.unwrap_with_pad(&cipher)).unwrap());
+        }
+        assert_eq!(
+            Err(KeyWrapError::InvalidCiphertextLength(8)),
+            exposed(kw.unwrap_with_pad(&[0u8; 8]))
+        );
     }
 }
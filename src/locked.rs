@@ -0,0 +1,174 @@
+use crate::KeyWrapError;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+use zeroize::Zeroize;
+
+// Locked memory for key buffers (`mlock` feature)
+//
+// KEK copies, unwrap working buffers and unwrapped keys are mlock'd before
+// any key material is written to them, so it never reaches swap. mlock and
+// munlock work on whole pages, which several key buffers may share, so
+// every locked page is reference counted: it is locked when the first
+// buffer on it is created and unlocked when the last one is dropped.
+
+#[cfg(feature = "mlock")]
+mod pages {
+    use crate::KeyWrapError;
+    use std::collections::BTreeMap;
+    use std::sync::{Mutex, MutexGuard};
+
+    // page address -> number of live buffers on it
+    static LOCKED: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+    fn locked() -> MutexGuard<'static, BTreeMap<usize, usize>> {
+        LOCKED.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // addresses of the pages `buf` touches
+    fn pages_of(buf: &[u8]) -> impl Iterator<Item = usize> {
+        let size = region::page::size();
+        let start = buf.as_ptr() as usize & !(size - 1);
+        let end = buf.as_ptr() as usize + buf.len();
+        (start..end).step_by(size)
+    }
+
+    fn release(map: &mut BTreeMap<usize, usize>, page: usize) {
+        if let Some(count) = map.get_mut(&page) {
+            *count -= 1;
+            if *count == 0 {
+                map.remove(&page);
+                let _ = region::unlock(page as *const u8, region::page::size());
+            }
+        }
+    }
+
+    pub(super) fn lock(buf: &[u8]) -> Result<(), KeyWrapError> {
+        let mut map = locked();
+        for (i, page) in pages_of(buf).enumerate() {
+            if !map.contains_key(&page) {
+                match region::lock(page as *const u8, region::page::size()) {
+                    // unlocked by `release` once the page count drops to 0
+                    Ok(guard) => core::mem::forget(guard),
+                    Err(_) => {
+                        for page in pages_of(buf).take(i) {
+                            release(&mut map, page);
+                        }
+                        return Err(KeyWrapError::MemoryLockFailed);
+                    }
+                }
+            }
+            *map.entry(page).or_insert(0) += 1;
+        }
+        Ok(())
+    }
+
+    pub(super) fn unlock(buf: &[u8]) {
+        let mut map = locked();
+        for page in pages_of(buf) {
+            release(&mut map, page);
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn count(page: usize) -> usize {
+        locked().get(&page).copied().unwrap_or(0)
+    }
+}
+
+/// Key buffer locked for as long as it lives; zeroized and unlocked on drop
+pub(crate) struct LockedBuf(Vec<u8>);
+
+impl Deref for LockedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DerefMut for LockedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl Drop for LockedBuf {
+    fn drop(&mut self) {
+        // the slice, not the Vec: `Vec::zeroize` also truncates it
+        self.0[..].zeroize();
+        #[cfg(feature = "mlock")]
+        if !self.0.is_empty() {
+            pages::unlock(&self.0);
+        }
+    }
+}
+
+/// Copy `data` into a freshly allocated buffer locked before the copy
+pub(crate) fn locked_copy(data: &[u8]) -> Result<LockedBuf, KeyWrapError> {
+    let buf = vec![0u8; data.len()];
+    #[cfg(feature = "mlock")]
+    if !buf.is_empty() {
+        pages::lock(&buf)?;
+    }
+    let mut buf = LockedBuf(buf);
+    buf.copy_from_slice(data);
+    Ok(buf)
+}
+
+#[cfg(all(test, feature = "mlock"))]
+mod tests {
+    use super::*;
+    use crate::{try_unwrap_key, AesKeyWrapper, Algorithm, KeyWrapper};
+
+    #[test]
+    fn test_locked_unwrap() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        assert_eq!(
+            &plain[..],
+            try_unwrap_key(&kek, &cipher).unwrap().expose_secret()
+        );
+        let wrapper = AesKeyWrapper::new(&kek, Algorithm::Kw).unwrap();
+        assert_eq!(&plain[..], wrapper.unwrap(&cipher).unwrap().expose_secret());
+    }
+
+    // VmLck of this process
+    #[cfg(target_os = "linux")]
+    fn locked_kib() -> usize {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap();
+        let line = status.lines().find(|l| l.starts_with("VmLck:")).unwrap();
+        line.split_whitespace().nth(1).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_pages_unlocked() {
+        let size = region::page::size();
+        let buf = vec![0u8; 3 * size];
+        // the second page lies wholly inside `buf`, so no other buffer
+        // can share it while `buf` is alive
+        let page = (buf.as_ptr() as usize & !(size - 1)) + size;
+        pages::lock(&buf).unwrap();
+        pages::lock(&buf[size..]).unwrap();
+        assert_eq!(pages::count(page), 2);
+        #[cfg(target_os = "linux")]
+        assert!(locked_kib() >= 3 * size / 1024);
+        pages::unlock(&buf[size..]);
+        assert_eq!(pages::count(page), 1);
+        pages::unlock(&buf);
+        assert_eq!(pages::count(page), 0);
+    }
+
+    #[test]
+    fn test_locked_buf_unlocks_on_drop() {
+        let size = region::page::size();
+        // large enough for the allocator to mmap it, so the pages go back
+        // to the OS on drop instead of being handed to another buffer
+        let buf = locked_copy(&vec![0x5a; 64 * size]).unwrap();
+        let page = (buf.as_ptr() as usize & !(size - 1)) + size;
+        assert_eq!(pages::count(page), 1);
+        drop(buf);
+        assert_eq!(pages::count(page), 0);
+    }
+}
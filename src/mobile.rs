@@ -0,0 +1,63 @@
+//! UniFFI exports for Swift and Kotlin
+//!
+//! Errors reach the foreign side as `KeyWrapError` cases carrying the
+//! `Display` message.
+//!
+//! Unwrapped keys cross the FFI as plain byte buffers: UniFFI serializes
+//! the returned `Vec` into a `RustBuffer` and drops it without zeroizing,
+//! and the foreign side frees that buffer the same way. Only the
+//! `UnwrappedKey` the key was unwrapped into is scrubbed.
+
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, KeyWrapError,
+};
+use alloc::vec::Vec;
+
+/// Wrap key with the IV defined in RFC3394
+#[uniffi::export]
+pub fn wrap_key(kek: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, KeyWrapError> {
+    try_wrap_key(&kek, &key)
+}
+
+/// Unwrap key and Check IV in RFC3394
+///
+/// The returned copy of the key is not zeroized, see the module docs.
+#[uniffi::export]
+pub fn unwrap_key(kek: Vec<u8>, wrapped: Vec<u8>) -> Result<Vec<u8>, KeyWrapError> {
+    try_unwrap_key(&kek, &wrapped).map(|key| key.expose_secret().to_vec())
+}
+
+/// Wrap key with pad using padding algorithm (RFC5649)
+#[uniffi::export]
+pub fn wrap_key_with_pad(kek: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, KeyWrapError> {
+    try_wrap_key_with_pad(&kek, &key)
+}
+
+/// Unwrap key with pad using padding algorithm (RFC5649)
+///
+/// The returned copy of the key is not zeroized, see the module docs.
+#[uniffi::export]
+pub fn unwrap_key_with_pad(kek: Vec<u8>, wrapped: Vec<u8>) -> Result<Vec<u8>, KeyWrapError> {
+    try_unwrap_key_with_pad(&kek, &wrapped).map(|key| key.expose_secret().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mobile_exports() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        assert_eq!(Ok(cipher.clone()), wrap_key(kek.clone(), plain.clone()));
+        assert_eq!(Ok(plain), unwrap_key(kek.clone(), cipher.clone()));
+        assert_eq!(
+            Err(KeyWrapError::IntegrityCheckFailed),
+            unwrap_key_with_pad(kek.clone(), cipher)
+        );
+
+        let wrapped = wrap_key_with_pad(kek.clone(), b"short".to_vec()).unwrap();
+        assert_eq!(Ok(b"short".to_vec()), unwrap_key_with_pad(kek, wrapped));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{Algorithm, KeyWrapError, KeyWrapper, UnwrappedKey, IV_3394};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// A call made on a `MockKeyWrapper`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockCall {
+    Wrap(Vec<u8>),
+    Unwrap(Vec<u8>),
+}
+
+/// Recording `KeyWrapper` for downstream tests
+///
+/// Every call is recorded. Results queued with `push_wrap_result` /
+/// `push_unwrap_result` (or the `fail_next_*` helpers) are returned first, in
+/// order; once the queue is empty the mock "wraps" by prefixing `IV_3394`
+/// and "unwraps" by checking and stripping it again, so round trips work
+/// without a real KEK.
+pub struct MockKeyWrapper {
+    algorithm: Algorithm,
+    calls: Mutex<Vec<MockCall>>,
+    wrap_results: Mutex<VecDeque<Result<Vec<u8>, KeyWrapError>>>,
+    unwrap_results: Mutex<VecDeque<Result<Vec<u8>, KeyWrapError>>>,
+}
+
+impl MockKeyWrapper {
+    pub fn new(algorithm: Algorithm) -> MockKeyWrapper {
+        MockKeyWrapper {
+            algorithm,
+            calls: Mutex::new(Vec::new()),
+            wrap_results: Mutex::new(VecDeque::new()),
+            unwrap_results: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Calls made so far, oldest first
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Queue the result of the next `wrap`
+    pub fn push_wrap_result(&self, result: Result<Vec<u8>, KeyWrapError>) {
+        self.wrap_results.lock().unwrap().push_back(result);
+    }
+
+    /// Queue the result of the next `unwrap`
+    pub fn push_unwrap_result(&self, result: Result<Vec<u8>, KeyWrapError>) {
+        self.unwrap_results.lock().unwrap().push_back(result);
+    }
+
+    /// Make the next `unwrap` fail as if the integrity check did
+    pub fn fail_next_unwrap_with_integrity_error(&self) {
+        self.push_unwrap_result(Err(KeyWrapError::IntegrityCheckFailed));
+    }
+
+    /// Make the next `wrap` fail as if a remote provider timed out
+    pub fn fail_next_wrap_with_timeout(&self) {
+        self.push_wrap_result(Err(KeyWrapError::ProviderUnavailable));
+    }
+
+    /// Make the next `unwrap` fail as if a remote provider timed out
+    pub fn fail_next_unwrap_with_timeout(&self) {
+        self.push_unwrap_result(Err(KeyWrapError::ProviderUnavailable));
+    }
+}
+
+impl KeyWrapper for MockKeyWrapper {
+    fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(MockCall::Wrap(plaintext.to_vec()));
+        if let Some(result) = self.wrap_results.lock().unwrap().pop_front() {
+            return result;
+        }
+
+        let mut wrapped = IV_3394.to_vec();
+        wrapped.extend_from_slice(plaintext);
+        Ok(wrapped)
+    }
+
+    fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(MockCall::Unwrap(wrapped.to_vec()));
+        if let Some(result) = self.unwrap_results.lock().unwrap().pop_front() {
+            return UnwrappedKey::from_slice(&result?);
+        }
+
+        if wrapped.len() < 8 || wrapped[..8] != IV_3394 {
+            return Err(KeyWrapError::IntegrityCheckFailed);
+        }
+        UnwrappedKey::from_slice(&wrapped[8..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_records_and_round_trips() {
+        let mock = MockKeyWrapper::new(Algorithm::Kw);
+        let wrapped = mock.wrap(b"0123456789abcdef").unwrap();
+        assert_eq!(
+            b"0123456789abcdef",
+            mock.unwrap(&wrapped).unwrap().expose_secret()
+        );
+        assert_eq!(
+            vec![
+                MockCall::Wrap(b"0123456789abcdef".to_vec()),
+                MockCall::Unwrap(wrapped)
+            ],
+            mock.calls()
+        );
+    }
+
+    #[test]
+    fn test_mock_simulated_failures() {
+        let mock = MockKeyWrapper::new(Algorithm::Kwp);
+        let wrapper: &dyn KeyWrapper = &mock;
+        mock.fail_next_wrap_with_timeout();
+        mock.fail_next_unwrap_with_integrity_error();
+        mock.push_unwrap_result(Ok(vec![1, 2, 3]));
+        assert_eq!(Err(KeyWrapError::ProviderUnavailable), wrapper.wrap(b"key"));
+        let wrapped = wrapper.wrap(b"key").unwrap();
+        assert_eq!(
+            Some(KeyWrapError::IntegrityCheckFailed),
+            wrapper.unwrap(&wrapped).err()
+        );
+        assert_eq!(
+            &[1, 2, 3],
+            wrapper.unwrap(&wrapped).unwrap().expose_secret()
+        );
+        assert_eq!(b"key", wrapper.unwrap(&wrapped).unwrap().expose_secret());
+        assert!(wrapper.unwrap(b"garbage").is_err());
+        assert_eq!(6, mock.calls().len());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::locked::{locked_copy, LockedBuf};
+use crate::{
+    check_kek_len, check_kw_block, check_kw_wrapped_len, check_kwp_block, check_kwp_wrapped_len,
+    kw_wrap_with, kwp_wrap_with, self_test, Aes128Kw, Aes192Kw, Aes256Kw, Algorithm, KeyWrapError,
+    UnwrappedKey,
+};
+use alloc::vec::Vec;
+
+// Multi-lane batch backend
+//
+// A single wrap is a chain of 6n dependent AES blocks, so AES-NI sits idle
+// waiting on each block's latency. Independent wraps of the same length are
+// instead processed `LANES` at a time in lockstep (see `Aes*Kw::w_lanes`),
+// encrypting one block of every lane per ECB call. Results come back in
+// input order and match the single-key functions exactly.
+
+pub(crate) const LANES: usize = 8;
+
+fn wrap_lanes(kek: &[u8], bufs: &mut [&mut [u8]]) -> Result<(), KeyWrapError> {
+    self_test::power_up()?;
+    match kek.len() {
+        16 => Aes128Kw::w_lanes(kek, bufs),
+        24 => Aes192Kw::w_lanes(kek, bufs),
+        32 => Aes256Kw::w_lanes(kek, bufs),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+fn unwrap_lanes(kek: &[u8], bufs: &mut [&mut [u8]]) -> Result<(), KeyWrapError> {
+    self_test::power_up()?;
+    match kek.len() {
+        16 => Aes128Kw::w_inv_lanes(kek, bufs),
+        24 => Aes192Kw::w_inv_lanes(kek, bufs),
+        32 => Aes256Kw::w_inv_lanes(kek, bufs),
+        len => Err(KeyWrapError::InvalidKekLength(len)),
+    }
+}
+
+// Run `f` over each group of equal-length buffers
+fn for_each_len_group<F>(mut bufs: Vec<&mut [u8]>, mut f: F) -> Result<(), KeyWrapError>
+where
+    F: FnMut(&mut [&mut [u8]]) -> Result<(), KeyWrapError>,
+{
+    bufs.sort_by_key(|b| b.len());
+    for group in bufs.chunk_by_mut(|a, b| a.len() == b.len()) {
+        f(group)?;
+    }
+    Ok(())
+}
+
+/// Wrap each key under `kek`, interleaving independent wraps of the same
+/// length
+pub fn wrap_keys_multilane<T: AsRef<[u8]>>(
+    kek: &[u8],
+    algorithm: Algorithm,
+    plaintexts: &[T],
+) -> Vec<Result<Vec<u8>, KeyWrapError>> {
+    if let Err(e) = check_kek_len(kek) {
+        return plaintexts.iter().map(|_| Err(e)).collect();
+    }
+
+    // AEAD modes have no W to interleave, each key is sealed on its own
+    let padded = match algorithm {
+        Algorithm::Kw => false,
+        Algorithm::Kwp => true,
+        #[cfg(feature = "gcm-siv")]
+        Algorithm::AesGcmSiv => {
+            return plaintexts
+                .iter()
+                .map(|p| crate::try_aes_gcm_siv_wrap_key(kek, p.as_ref(), &[]))
+                .collect()
+        }
+        #[cfg(feature = "gcm")]
+        Algorithm::AesGcm => {
+            return plaintexts
+                .iter()
+                .map(|p| crate::try_aes_gcm_wrap_key(kek, p.as_ref(), &[]))
+                .collect()
+        }
+        #[cfg(feature = "chacha")]
+        Algorithm::ChaCha20Poly1305 => {
+            return plaintexts
+                .iter()
+                .map(|p| crate::try_chacha20poly1305_wrap_key(kek, p.as_ref(), &[]))
+                .collect()
+        }
+    };
+
+    // framing only; W runs below, across lanes
+    let mut items: Vec<Result<Vec<u8>, KeyWrapError>> = plaintexts
+        .iter()
+        .map(|p| {
+            if padded {
+                kwp_wrap_with(p.as_ref(), |_| Ok(()))
+            } else {
+                kw_wrap_with(p.as_ref(), |_| Ok(()))
+            }
+        })
+        .collect();
+    let bufs = items
+        .iter_mut()
+        .filter_map(|item| item.as_mut().ok())
+        .map(|b| &mut b[..])
+        .collect();
+    if let Err(e) = for_each_len_group(bufs, |group| wrap_lanes(kek, group)) {
+        return plaintexts.iter().map(|_| Err(e)).collect();
+    }
+    items
+}
+
+/// Unwrap each key under `kek`, interleaving independent unwraps of the
+/// same length
+pub fn unwrap_keys_multilane<T: AsRef<[u8]>>(
+    kek: &[u8],
+    algorithm: Algorithm,
+    wrapped: &[T],
+) -> Vec<Result<UnwrappedKey, KeyWrapError>> {
+    if let Err(e) = check_kek_len(kek) {
+        return wrapped.iter().map(|_| Err(e)).collect();
+    }
+
+    let padded = match algorithm {
+        Algorithm::Kw => false,
+        Algorithm::Kwp => true,
+        #[cfg(feature = "gcm-siv")]
+        Algorithm::AesGcmSiv => {
+            return wrapped
+                .iter()
+                .map(|w| crate::try_aes_gcm_siv_unwrap_key(kek, w.as_ref(), &[]))
+                .collect()
+        }
+        #[cfg(feature = "gcm")]
+        Algorithm::AesGcm => {
+            return wrapped
+                .iter()
+                .map(|w| crate::try_aes_gcm_unwrap_key(kek, w.as_ref(), &[]))
+                .collect()
+        }
+        #[cfg(feature = "chacha")]
+        Algorithm::ChaCha20Poly1305 => {
+            return wrapped
+                .iter()
+                .map(|w| crate::try_chacha20poly1305_unwrap_key(kek, w.as_ref(), &[]))
+                .collect()
+        }
+    };
+
+    let mut items: Vec<Result<LockedBuf, KeyWrapError>> = wrapped
+        .iter()
+        .map(|w| {
+            let w = w.as_ref();
+            if padded {
+                check_kwp_wrapped_len(w.len())?;
+            } else {
+                check_kw_wrapped_len(w.len())?;
+            }
+            locked_copy(w)
+        })
+        .collect();
+    let bufs = items
+        .iter_mut()
+        .filter_map(|item| item.as_mut().ok())
+        .map(|b| &mut b[..])
+        .collect();
+    if let Err(e) = for_each_len_group(bufs, |group| unwrap_lanes(kek, group)) {
+        return wrapped.iter().map(|_| Err(e)).collect();
+    }
+
+    items
+        .into_iter()
+        .map(|buf| {
+            let buf = buf?;
+            if padded {
+                let key_len = check_kwp_block(&buf)?;
+                UnwrappedKey::from_slice(&buf[8..8 + key_len])
+            } else {
+                check_kw_block(&buf)?;
+                UnwrappedKey::from_slice(&buf[8..])
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad};
+
+    #[test]
+    fn test_multilane_matches_single_key() {
+        for kek in [&[0x11u8; 16][..], &[0x22u8; 24][..], &[0x33u8; 32][..]].iter() {
+            // more keys than lanes, mixed lengths, one invalid for KW
+            let keys: Vec<Vec<u8>> = (0..21u8)
+                .map(|n| vec![n; [16, 24, 7, 40][n as usize % 4]])
+                .collect();
+
+            let wrapped = wrap_keys_multilane(kek, Algorithm::Kw, &keys);
+            for (key, w) in keys.iter().zip(&wrapped) {
+                assert_eq!(&try_wrap_key(kek, key), w);
+            }
+            let wrapped = wrap_keys_multilane(kek, Algorithm::Kwp, &keys);
+            let wrapped: Vec<Vec<u8>> = wrapped.into_iter().map(Result::unwrap).collect();
+            for (key, w) in keys.iter().zip(&wrapped) {
+                assert_eq!(&try_wrap_key_with_pad(kek, key).unwrap(), w);
+            }
+
+            let mut tampered = wrapped.clone();
+            tampered[5][9] ^= 1;
+            let unwrapped = unwrap_keys_multilane(kek, Algorithm::Kwp, &tampered);
+            for (w, u) in tampered.iter().zip(unwrapped) {
+                assert_eq!(
+                    try_unwrap_key_with_pad(kek, w).map(|k| k.expose_secret().to_vec()),
+                    u.map(|k| k.expose_secret().to_vec())
+                );
+            }
+            let unwrapped = unwrap_keys_multilane(kek, Algorithm::Kw, &tampered);
+            for (w, u) in tampered.iter().zip(unwrapped) {
+                assert_eq!(
+                    try_unwrap_key(kek, w).map(|k| k.expose_secret().to_vec()),
+                    u.map(|k| k.expose_secret().to_vec())
+                );
+            }
+        }
+
+        assert_eq!(
+            vec![Err(KeyWrapError::InvalidKekLength(15))],
+            wrap_keys_multilane(&[0u8; 15], Algorithm::Kw, &[[0u8; 16]])
+        );
+    }
+}
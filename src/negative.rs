@@ -0,0 +1,252 @@
+use crate::{
+    aes_unwrap_key_and_iv, aes_unwrap_key_with_pad, aes_wrap_key_and_iv, wrap_in_place, IV_3394,
+    IV_5649,
+};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+// Negative test-vector generator
+//
+// Starting from a valid wrapped key, derive blobs that a conforming unwrap
+// must reject. Tampering that has to survive decryption (a specific ICV
+// bit, a bad MLI, non-zero padding) is produced by re-wrapping under the
+// KEK; everything else is applied to the ciphertext directly.
+
+/// How a negative vector was derived from the valid blob
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Tamper {
+    /// One bit of the recovered ICV (A) differs from the expected IV/AIV
+    FlippedIcvBit { bit: usize },
+    /// One bit of the ciphertext flipped
+    FlippedCiphertextBit { bit: usize },
+    /// Last semiblock removed
+    TruncatedSemiblock,
+    /// Last octet removed, leaving a length that is not a multiple of 8
+    TruncatedOctet,
+    /// Two ciphertext semiblocks exchanged
+    SwappedSemiblocks,
+    /// KWP message length indicator outside 8*(n-1) < MLI <= 8*n
+    ModifiedMli { mli: u32 },
+    /// KWP padding octet set to a non-zero value
+    NonZeroPadding,
+}
+
+/// Failure a conforming implementation must report for a negative vector
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExpectedFailure {
+    /// Recovered ICV/AIV doesn't match
+    IntegrityCheck,
+    /// Wrapped input has an impossible length
+    InvalidLength,
+    /// KWP message length indicator out of range
+    InvalidMli,
+    /// KWP padding octets are not zero
+    InvalidPadding,
+}
+
+/// A tampered wrapped key and the failure it must produce
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NegativeVector {
+    pub tamper: Tamper,
+    pub wrapped: Vec<u8>,
+    pub expected: ExpectedFailure,
+}
+
+fn ciphertext_variants(wrapped: &[u8], min_len: usize) -> Vec<NegativeVector> {
+    let mut vectors = Vec::new();
+    for &bit in [0, wrapped.len() * 4, wrapped.len() * 8 - 1].iter() {
+        let mut tampered = wrapped.to_vec();
+        tampered[bit / 8] ^= 0x80 >> (bit % 8);
+        vectors.push(NegativeVector {
+            tamper: Tamper::FlippedCiphertextBit { bit },
+            wrapped: tampered,
+            expected: ExpectedFailure::IntegrityCheck,
+        });
+    }
+
+    let truncated = wrapped[..wrapped.len() - 8].to_vec();
+    vectors.push(NegativeVector {
+        tamper: Tamper::TruncatedSemiblock,
+        expected: if truncated.len() < min_len {
+            ExpectedFailure::InvalidLength
+        } else {
+            ExpectedFailure::IntegrityCheck
+        },
+        wrapped: truncated,
+    });
+    vectors.push(NegativeVector {
+        tamper: Tamper::TruncatedOctet,
+        wrapped: wrapped[..wrapped.len() - 1].to_vec(),
+        expected: ExpectedFailure::InvalidLength,
+    });
+
+    if wrapped.len() >= 24 {
+        let mut swapped = wrapped.to_vec();
+        let last = wrapped.len() - 8;
+        swapped[8..16].copy_from_slice(&wrapped[last..]);
+        swapped[last..].copy_from_slice(&wrapped[8..16]);
+        vectors.push(NegativeVector {
+            tamper: Tamper::SwappedSemiblocks,
+            wrapped: swapped,
+            expected: ExpectedFailure::IntegrityCheck,
+        });
+    }
+
+    vectors
+}
+
+/// Negative vectors derived from a valid RFC3394 wrapped key
+pub fn kw_negative_vectors(kek: &[u8], wrapped: &[u8]) -> Result<Vec<NegativeVector>, String> {
+    let (key, iv) = aes_unwrap_key_and_iv(kek, wrapped)?;
+    if iv != IV_3394 {
+        return Err(String::from("Key IV error"));
+    }
+
+    let mut vectors = Vec::new();
+    for bit in 0..64 {
+        let mut icv = IV_3394;
+        icv[bit / 8] ^= 0x80 >> (bit % 8);
+        vectors.push(NegativeVector {
+            tamper: Tamper::FlippedIcvBit { bit },
+            wrapped: aes_wrap_key_and_iv(kek, &key, &icv)?,
+            expected: ExpectedFailure::IntegrityCheck,
+        });
+    }
+    vectors.extend(ciphertext_variants(wrapped, 24));
+
+    Ok(vectors)
+}
+
+// W over AIV | padded plaintext with an arbitrary (possibly invalid) AIV
+fn kwp_rewrap(kek: &[u8], aiv: &[u8; 8], padded: &[u8]) -> Result<Vec<u8>, String> {
+    let mut buf = aiv.to_vec();
+    buf.extend_from_slice(padded);
+    wrap_in_place(kek, &mut buf)?;
+    Ok(buf)
+}
+
+/// Negative vectors derived from a valid RFC5649 wrapped key
+pub fn kwp_negative_vectors(kek: &[u8], wrapped: &[u8]) -> Result<Vec<NegativeVector>, String> {
+    let key = aes_unwrap_key_with_pad(kek, wrapped)?;
+    let mut padded = key.clone();
+    padded.resize(wrapped.len() - 8, 0);
+    let mut aiv = [0u8; 8];
+    aiv[..4].copy_from_slice(&IV_5649);
+    aiv[4..].copy_from_slice(&(key.len() as u32).to_be_bytes());
+
+    let mut vectors = Vec::new();
+    for bit in 0..32 {
+        let mut icv = aiv;
+        icv[bit / 8] ^= 0x80 >> (bit % 8);
+        vectors.push(NegativeVector {
+            tamper: Tamper::FlippedIcvBit { bit },
+            wrapped: kwp_rewrap(kek, &icv, &padded)?,
+            expected: ExpectedFailure::IntegrityCheck,
+        });
+    }
+
+    let padded_len = padded.len() as u32;
+    let mut bad_mlis = vec![0, padded_len + 1, padded_len + 8, u32::MAX];
+    if padded_len > 8 {
+        bad_mlis.push(padded_len - 8);
+    }
+    for mli in bad_mlis {
+        let mut icv = aiv;
+        icv[4..].copy_from_slice(&mli.to_be_bytes());
+        vectors.push(NegativeVector {
+            tamper: Tamper::ModifiedMli { mli },
+            wrapped: kwp_rewrap(kek, &icv, &padded)?,
+            expected: ExpectedFailure::InvalidMli,
+        });
+    }
+
+    if key.len() < padded.len() {
+        let mut bad_padding = padded.clone();
+        *bad_padding.last_mut().unwrap() = 0x01;
+        vectors.push(NegativeVector {
+            tamper: Tamper::NonZeroPadding,
+            wrapped: kwp_rewrap(kek, &aiv, &bad_padding)?,
+            expected: ExpectedFailure::InvalidPadding,
+        });
+    }
+    vectors.extend(ciphertext_variants(wrapped, 16));
+
+    Ok(vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{try_unwrap_key, try_unwrap_key_with_pad, KeyWrapError, UnwrappedKey};
+
+    // the unwrap must fail, and with the error `v.expected` names
+    fn assert_fails_as(v: &NegativeVector, result: Result<UnwrappedKey, KeyWrapError>) {
+        let err = result.err();
+        assert!(
+            matches!(
+                (v.expected, err),
+                (
+                    ExpectedFailure::IntegrityCheck,
+                    Some(KeyWrapError::IntegrityCheckFailed)
+                ) | (
+                    ExpectedFailure::InvalidLength,
+                    Some(KeyWrapError::InvalidCiphertextLength(_))
+                ) | (
+                    ExpectedFailure::InvalidMli,
+                    Some(KeyWrapError::InvalidMli(_))
+                ) | (
+                    ExpectedFailure::InvalidPadding,
+                    Some(KeyWrapError::InvalidPadding)
+                )
+            ),
+            "{:?}: expected {:?}, got {:?}",
+            v.tamper,
+            v.expected,
+            err
+        );
+    }
+
+    #[test]
+    fn test_kw_negative_vectors() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let wrapped = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let vectors = kw_negative_vectors(&kek, &wrapped).unwrap();
+        assert_eq!(64 + 3 + 3, vectors.len());
+        for v in vectors.iter() {
+            assert_ne!(wrapped, v.wrapped);
+            assert_fails_as(v, try_unwrap_key(&kek, &v.wrapped));
+        }
+        assert!(kw_negative_vectors(&kek, &vectors[0].wrapped).is_err());
+    }
+
+    #[test]
+    fn test_kwp_negative_vectors() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let wrapped =
+            hex::decode("138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A")
+                .unwrap();
+        let vectors = kwp_negative_vectors(&kek, &wrapped).unwrap();
+        assert!(vectors.iter().any(|v| v.tamper == Tamper::NonZeroPadding));
+        assert!(vectors
+            .iter()
+            .any(|v| v.expected == ExpectedFailure::InvalidMli));
+        for v in vectors.iter() {
+            assert_eq!(
+                v.expected == ExpectedFailure::InvalidLength,
+                v.wrapped.len() % 8 != 0
+            );
+            assert_fails_as(v, try_unwrap_key_with_pad(&kek, &v.wrapped));
+        }
+
+        let short = hex::decode("AFBEB0F07DFBF5419200F2CCB50BB24F").unwrap();
+        let vectors = kwp_negative_vectors(&kek, &short).unwrap();
+        for v in vectors.iter() {
+            assert_fails_as(v, try_unwrap_key_with_pad(&kek, &v.wrapped));
+        }
+        assert!(vectors
+            .iter()
+            .any(|v| v.tamper == Tamper::TruncatedSemiblock
+                && v.expected == ExpectedFailure::InvalidLength));
+    }
+}
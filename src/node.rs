@@ -0,0 +1,81 @@
+//! napi-rs exports for Node.js, `Buffer` in and out
+//!
+//! Unwrapped keys are copied out of their `UnwrappedKey` into the `Vec`
+//! backing a new `Buffer`, which JS owns and V8 frees without zeroizing. Only the `UnwrappedKey` itself is scrubbed; callers should
+//! `fill(0)` the returned `Buffer` once they are done with the key.
+
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, KeyWrapError,
+    UnwrappedKey,
+};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+
+// `Error` on the JS side, with the `KeyWrapError` message
+fn js_error(e: KeyWrapError) -> napi::Error {
+    napi::Error::from_reason(e.to_string())
+}
+
+// the copy of the key handed to JS, `Buffer::from` takes the `Vec` as is
+fn key_bytes(key: UnwrappedKey) -> Vec<u8> {
+    key.expose_secret().to_vec()
+}
+
+/// Wrap key with the IV defined in RFC3394 (`wrapKey(kek, key)` in JS)
+#[napi]
+pub fn wrap_key(kek: &[u8], key: &[u8]) -> napi::Result<Buffer> {
+    try_wrap_key(kek, key).map(Buffer::from).map_err(js_error)
+}
+
+/// Unwrap key and Check IV in RFC3394 (`unwrapKey(kek, wrapped)` in JS)
+///
+/// The returned copy of the key is not zeroized, see the module docs.
+#[napi]
+pub fn unwrap_key(kek: &[u8], wrapped: &[u8]) -> napi::Result<Buffer> {
+    try_unwrap_key(kek, wrapped)
+        .map(|key| Buffer::from(key_bytes(key)))
+        .map_err(js_error)
+}
+
+/// Wrap key with pad using padding algorithm (RFC5649) (`wrapKeyWithPad` in JS)
+#[napi]
+pub fn wrap_key_with_pad(kek: &[u8], key: &[u8]) -> napi::Result<Buffer> {
+    try_wrap_key_with_pad(kek, key)
+        .map(Buffer::from)
+        .map_err(js_error)
+}
+
+/// Unwrap key with pad using padding algorithm (RFC5649) (`unwrapKeyWithPad` in JS)
+///
+/// The returned copy of the key is not zeroized, see the module docs.
+#[napi]
+pub fn unwrap_key_with_pad(kek: &[u8], wrapped: &[u8]) -> napi::Result<Buffer> {
+    try_unwrap_key_with_pad(kek, wrapped)
+        .map(|key| Buffer::from(key_bytes(key)))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // only the Rust side: napi's Buffer and Error call into N-API, which only Node provides
+    #[test]
+    fn test_node_exports() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        assert_eq!(cipher, try_wrap_key(&kek, &plain).unwrap());
+        assert_eq!(plain, try_unwrap_key(&kek, &cipher).map(key_bytes).unwrap());
+
+        let wrapped = try_wrap_key_with_pad(&kek, b"short").unwrap();
+        assert_eq!(
+            b"short".to_vec(),
+            try_unwrap_key_with_pad(&kek, &wrapped)
+                .map(key_bytes)
+                .unwrap()
+        );
+    }
+}
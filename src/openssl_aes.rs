@@ -0,0 +1,140 @@
+// AES block function from OpenSSL (`backend-openssl` feature)
+//
+// Every block encryption goes through libcrypto's EVP AES-ECB, so a
+// deployment that must use a FIPS-validated module (OpenSSL 3 with the FIPS
+// provider as the default) gets one without changing any call site; this
+// crate only supplies the RFC3394/RFC5649 chaining around it.
+//
+// The types follow crypto2's `blockmode::AesXxxEcb` API (new/encrypt/decrypt)
+// like `soft_aes`, except that each call returns `BlockCipherFailed` when
+// OpenSSL refuses (e.g. a FIPS provider in its error state) rather than
+// panicking. An EVP context can't be cloned, so `try_clone` keys fresh
+// contexts from the KEK copy kept alongside them.
+
+use crate::KeyWrapError;
+use openssl::symm::{Cipher, Crypter, Mode};
+use zeroize::{Zeroize, Zeroizing};
+
+const BLOCK_LEN: usize = 16;
+const PAR_BLOCKS: usize = 8;
+
+fn crypter(cipher: Cipher, mode: Mode, key: &[u8]) -> Result<Crypter, KeyWrapError> {
+    let mut crypter =
+        Crypter::new(cipher, mode, key, None).map_err(|_| KeyWrapError::BlockCipherFailed)?;
+    crypter.pad(false);
+    Ok(crypter)
+}
+
+// Run whole blocks through `crypter`, `PAR_BLOCKS` at a time
+fn update(crypter: &mut Crypter, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+    assert_eq!(blocks.len() % BLOCK_LEN, 0);
+
+    // EVP wants room for one extra block of output
+    let mut out = Zeroizing::new([0u8; BLOCK_LEN * (PAR_BLOCKS + 1)]);
+    for chunk in blocks.chunks_mut(BLOCK_LEN * PAR_BLOCKS) {
+        match crypter.update(chunk, &mut out[..]) {
+            Ok(n) if n == chunk.len() => chunk.copy_from_slice(&out[..n]),
+            _ => {
+                blocks.zeroize();
+                return Err(KeyWrapError::BlockCipherFailed);
+            }
+        }
+    }
+    Ok(())
+}
+
+macro_rules! impl_openssl_ecb {
+    ($name:tt, $cipher:expr, $key_len:expr) => {
+        pub struct $name {
+            key: Zeroizing<[u8; $key_len]>,
+            enc: Crypter,
+            dec: Crypter,
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize = $key_len;
+
+            pub fn new(key: &[u8]) -> Result<Self, KeyWrapError> {
+                assert_eq!(key.len(), Self::KEY_LEN);
+
+                let mut k = Zeroizing::new([0u8; $key_len]);
+                k.copy_from_slice(key);
+                Ok(Self {
+                    enc: crypter($cipher, Mode::Encrypt, key)?,
+                    dec: crypter($cipher, Mode::Decrypt, key)?,
+                    key: k,
+                })
+            }
+
+            pub fn try_clone(&self) -> Result<Self, KeyWrapError> {
+                Self::new(&self.key[..])
+            }
+
+            pub fn encrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                update(&mut self.enc, blocks)
+            }
+
+            pub fn decrypt(&mut self, blocks: &mut [u8]) -> Result<(), KeyWrapError> {
+                update(&mut self.dec, blocks)
+            }
+        }
+    };
+}
+
+impl_openssl_ecb!(Aes128Ecb, Cipher::aes_128_ecb(), 16);
+impl_openssl_ecb!(Aes192Ecb, Cipher::aes_192_ecb(), 24);
+impl_openssl_ecb!(Aes256Ecb, Cipher::aes_256_ecb(), 32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FIPS 197 appendix C
+    #[test]
+    fn test_fips197_vectors() {
+        let plain = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        for (key, cipher) in [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ]
+        .iter()
+        {
+            let key = hex::decode(key).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            // more blocks than one EVP update
+            let mut blocks = plain.repeat(PAR_BLOCKS + 3);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).unwrap().encrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).unwrap().encrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key)
+                    .unwrap()
+                    .try_clone()
+                    .unwrap()
+                    .encrypt(&mut blocks),
+            }
+            .unwrap();
+            assert_eq!(cipher.repeat(PAR_BLOCKS + 3), blocks);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).unwrap().decrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).unwrap().decrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key)
+                    .unwrap()
+                    .try_clone()
+                    .unwrap()
+                    .decrypt(&mut blocks),
+            }
+            .unwrap();
+            assert_eq!(plain.repeat(PAR_BLOCKS + 3), blocks);
+        }
+    }
+}
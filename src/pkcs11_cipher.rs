@@ -0,0 +1,197 @@
+use crate::pkcs11_uri::Pkcs11Uri;
+use crate::BlockCipher128;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use cryptoki::context::Pkcs11;
+use cryptoki::error::Error;
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::Session;
+use cryptoki::slot::TokenInfo;
+use zeroize::Zeroizing;
+
+// PKCS#11-delegated block cipher (`pkcs11` feature)
+//
+// For HSMs that expose raw CKM_AES_ECB but not CKM_AES_KEY_WRAP(_KWP): every
+// block of W / W^-1 is one C_Encrypt / C_Decrypt under the token's key
+// object, so the KEK never leaves the HSM while `BlockCipherKw` does the
+// RFC3394/RFC5649 chaining. That is 6n token round trips per wrap, so it
+// suits KEK-sized payloads, not bulk data.
+
+/// AES key object on a PKCS#11 token, as a `BlockCipher128`
+///
+/// ```ignore
+/// let uri: Pkcs11Uri = "pkcs11:token=hsm;object=kek".parse()?;
+/// let mut kw = BlockCipherKw::new(Pkcs11Cipher::from_uri(&pkcs11, session, &uri)?);
+/// let wrapped = kw.wrap_with_pad(&dek)?;
+/// ```
+pub struct Pkcs11Cipher {
+    session: Session,
+    key: ObjectHandle,
+}
+
+impl fmt::Debug for Pkcs11Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Pkcs11Cipher({})", self.key)
+    }
+}
+
+impl Pkcs11Cipher {
+    /// Use `key`, an AES secret key with CKA_ENCRYPT / CKA_DECRYPT, through
+    /// a logged-in `session`
+    pub fn new(session: Session, key: ObjectHandle) -> Self {
+        Pkcs11Cipher { session, key }
+    }
+
+    /// Find the AES secret key matching the `object` (CKA_LABEL) and `id`
+    /// (CKA_ID) attributes of `uri`; exactly one must match
+    ///
+    /// `session` must be open on the token the `slot-id`, `token`,
+    /// `manufacturer`, `serial` and `model` attributes designate, or this
+    /// fails. The `pin-*` and `module-*` attributes say how to open and log
+    /// in to that session, which the caller has already done with `pkcs11`.
+    pub fn from_uri(pkcs11: &Pkcs11, session: Session, uri: &Pkcs11Uri) -> Result<Self, String> {
+        let slot = session
+            .get_session_info()
+            .map_err(|e| format!("Session info query failed: {}", e))?
+            .slot_id();
+        let token = pkcs11
+            .get_token_info(slot)
+            .map_err(|e| format!("Token info query failed: {}", e))?;
+        check_token(uri, slot.id(), &token_attributes(&token))?;
+
+        let template = key_template(uri)?;
+        let keys = session
+            .find_objects(&template)
+            .map_err(|e| format!("Key lookup failed: {}", e))?;
+        match keys[..] {
+            [key] => Ok(Pkcs11Cipher::new(session, key)),
+            [] => Err(String::from("No AES key matches the URI")),
+            _ => Err(format!("{} AES keys match the URI", keys.len())),
+        }
+    }
+
+    /// Give the session back
+    pub fn into_session(self) -> Session {
+        self.session
+    }
+}
+
+// token label, manufacturer, serial and model, without CK_TOKEN_INFO's
+// blank padding
+fn token_attributes(token: &TokenInfo) -> [&str; 4] {
+    [
+        token.label(),
+        token.manufacturer_id(),
+        token.serial_number(),
+        token.model(),
+    ]
+    .map(|s| s.trim_end_matches(' '))
+}
+
+// Refuse a session on a token other than the one the URI designates;
+// `token` as returned by `token_attributes`
+fn check_token(uri: &Pkcs11Uri, slot_id: u64, token: &[&str; 4]) -> Result<(), String> {
+    if uri.slot_id.is_some_and(|id| id != slot_id) {
+        return Err(String::from("Session is not on the URI's slot-id"));
+    }
+    let wanted = [&uri.token, &uri.manufacturer, &uri.serial, &uri.model];
+    let names = ["token", "manufacturer", "serial", "model"];
+    for ((want, have), name) in wanted.iter().zip(token).zip(names) {
+        if want.as_deref().is_some_and(|w| w != *have) {
+            return Err(format!("Session token does not match the URI's {}", name));
+        }
+    }
+    Ok(())
+}
+
+// C_FindObjects template for the AES secret key a URI designates
+fn key_template(uri: &Pkcs11Uri) -> Result<Vec<Attribute>, String> {
+    if let Some(t) = uri.object_type.as_deref().filter(|t| *t != "secret-key") {
+        return Err(format!("Not a secret key object type: {}", t));
+    }
+
+    let mut template: Vec<Attribute> = Vec::with_capacity(4);
+    template.push(Attribute::Class(ObjectClass::SECRET_KEY));
+    template.push(Attribute::KeyType(KeyType::AES));
+    if let Some(label) = &uri.label {
+        template.push(Attribute::Label(label.as_bytes().to_vec()));
+    }
+    if let Some(id) = &uri.id {
+        template.push(Attribute::Id(id.clone()));
+    }
+    Ok(template)
+}
+
+impl BlockCipher128 for Pkcs11Cipher {
+    type Error = Error;
+
+    fn encrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Error> {
+        let out = Zeroizing::new(self.session.encrypt(&Mechanism::AesEcb, self.key, block)?);
+        block.copy_from_slice(&out[..16]);
+        Ok(())
+    }
+
+    fn decrypt_block(&mut self, block: &mut [u8; 16]) -> Result<(), Error> {
+        let out = Zeroizing::new(self.session.decrypt(&Mechanism::AesEcb, self.key, block)?);
+        block.copy_from_slice(&out[..16]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_template() {
+        let uri =
+            Pkcs11Uri::parse("pkcs11:token=hsm;object=kek;id=%01%02;type=secret-key").unwrap();
+        assert_eq!(
+            vec![
+                Attribute::Class(ObjectClass::SECRET_KEY),
+                Attribute::KeyType(KeyType::AES),
+                Attribute::Label(b"kek".to_vec()),
+                Attribute::Id(vec![1, 2]),
+            ],
+            key_template(&uri).unwrap()
+        );
+
+        let uri = Pkcs11Uri::parse("pkcs11:object=kek;type=private").unwrap();
+        assert_eq!(
+            Err(String::from("Not a secret key object type: private")),
+            key_template(&uri)
+        );
+    }
+
+    #[test]
+    fn test_check_token() {
+        let token = ["hsm", "Acme", "0042", "HSM-9"];
+        let uri = Pkcs11Uri::parse(
+            "pkcs11:token=hsm;manufacturer=Acme;serial=0042;model=HSM-9;slot-id=3;\
+             object=kek?pin-source=file:/run/pin&module-name=acme",
+        )
+        .unwrap();
+        assert_eq!(Ok(()), check_token(&uri, 3, &token));
+        assert_eq!(
+            Err(String::from("Session is not on the URI's slot-id")),
+            check_token(&uri, 4, &token)
+        );
+        assert_eq!(
+            Err(String::from("Session token does not match the URI's token")),
+            check_token(&uri, 3, &["other", "Acme", "0042", "HSM-9"])
+        );
+        assert_eq!(
+            Err(String::from(
+                "Session token does not match the URI's serial"
+            )),
+            check_token(&uri, 3, &["hsm", "Acme", "0043", "HSM-9"])
+        );
+
+        // attributes left out match any token
+        let uri = Pkcs11Uri::parse("pkcs11:object=kek").unwrap();
+        assert_eq!(Ok(()), check_token(&uri, 7, &["other", "", "", ""]));
+    }
+}
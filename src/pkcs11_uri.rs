@@ -0,0 +1,290 @@
+// PKCS#11 URI parser
+// * RFC 7512 "The PKCS #11 URI Scheme"
+//   https://tools.ietf.org/html/rfc7512.html
+//
+// Only the attributes needed to designate a KEK object are kept; vendor
+// specific attributes ("x-" prefix) are accepted and ignored. Errors name
+// the offending attribute but never echo its value, as it may be the PIN.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use zeroize::{Zeroize, Zeroizing};
+
+/// A parsed `pkcs11:` URI designating a key object on a token
+///
+/// `Debug` leaves out the `pin-value`.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Pkcs11Uri {
+    /// `token` path attribute (CK_TOKEN_INFO label)
+    pub token: Option<String>,
+    /// `manufacturer` path attribute
+    pub manufacturer: Option<String>,
+    /// `serial` path attribute
+    pub serial: Option<String>,
+    /// `model` path attribute
+    pub model: Option<String>,
+    /// `object` path attribute (CKA_LABEL)
+    pub label: Option<String>,
+    /// `id` path attribute (CKA_ID), raw bytes
+    pub id: Option<Vec<u8>>,
+    /// `type` path attribute (e.g. "secret-key")
+    pub object_type: Option<String>,
+    /// `slot-id` path attribute
+    pub slot_id: Option<u64>,
+    /// `pin-source` query attribute
+    pub pin_source: Option<String>,
+    /// `pin-value` query attribute, zeroized on drop
+    pub pin_value: Option<Zeroizing<String>>,
+    /// `module-name` query attribute
+    pub module_name: Option<String>,
+    /// `module-path` query attribute
+    pub module_path: Option<String>,
+}
+
+impl fmt::Debug for Pkcs11Uri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pkcs11Uri")
+            .field("token", &self.token)
+            .field("manufacturer", &self.manufacturer)
+            .field("serial", &self.serial)
+            .field("model", &self.model)
+            .field("label", &self.label)
+            .field("id", &self.id)
+            .field("object_type", &self.object_type)
+            .field("slot_id", &self.slot_id)
+            .field("pin_source", &self.pin_source)
+            .field(
+                "pin_value",
+                &self
+                    .pin_value
+                    .as_ref()
+                    .map(|_| format_args!("..redacted..")),
+            )
+            .field("module_name", &self.module_name)
+            .field("module_path", &self.module_path)
+            .finish()
+    }
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn pct_decode(name: &str, value: &str) -> Result<Vec<u8>, String> {
+    let bytes = value.as_bytes();
+    // partial output of a malformed PIN is scrubbed too
+    let mut out = Zeroizing::new(Vec::with_capacity(bytes.len()));
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if i + 2 >= bytes.len() {
+                return Err(format!("Truncated percent-encoding in {}", name));
+            }
+            match (hex_digit(bytes[i + 1]), hex_digit(bytes[i + 2])) {
+                (Some(h), Some(l)) => out.push(h << 4 | l),
+                _ => return Err(format!("Invalid percent-encoding in {}", name)),
+            }
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    Ok(core::mem::take(&mut *out))
+}
+
+fn pct_decode_str(name: &str, value: &str) -> Result<String, String> {
+    String::from_utf8(pct_decode(name, value)?).map_err(|e| {
+        e.into_bytes().zeroize();
+        format!("Invalid UTF-8 value in {}", name)
+    })
+}
+
+fn set_once<T>(slot: &mut Option<T>, name: &str, value: T) -> Result<(), String> {
+    if slot.is_some() {
+        return Err(format!("Duplicate attribute: {}", name));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+impl Pkcs11Uri {
+    /// Parse a `pkcs11:` URI (RFC 7512)
+    pub fn parse(uri: &str) -> Result<Pkcs11Uri, String> {
+        let rest = match uri.get(..7) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("pkcs11:") => &uri[7..],
+            _ => return Err(String::from("Not a pkcs11 URI")),
+        };
+        let (path, query) = match rest.find('?') {
+            Some(pos) => (&rest[..pos], Some(&rest[pos + 1..])),
+            None => (rest, None),
+        };
+
+        let mut parsed = Pkcs11Uri::default();
+        for attr in path.split(';').filter(|a| !a.is_empty()) {
+            let (name, value) = split_attr(attr)?;
+            match name {
+                "token" => set_once(&mut parsed.token, name, pct_decode_str(name, value)?)?,
+                "manufacturer" => {
+                    set_once(&mut parsed.manufacturer, name, pct_decode_str(name, value)?)?
+                }
+                "serial" => set_once(&mut parsed.serial, name, pct_decode_str(name, value)?)?,
+                "model" => set_once(&mut parsed.model, name, pct_decode_str(name, value)?)?,
+                "object" => set_once(&mut parsed.label, name, pct_decode_str(name, value)?)?,
+                "id" => set_once(&mut parsed.id, name, pct_decode(name, value)?)?,
+                "type" => set_once(&mut parsed.object_type, name, pct_decode_str(name, value)?)?,
+                "slot-id" => {
+                    let slot_id = pct_decode_str(name, value)?
+                        .parse::<u64>()
+                        .map_err(|_| String::from("Invalid slot-id"))?;
+                    set_once(&mut parsed.slot_id, name, slot_id)?
+                }
+                "library-manufacturer"
+                | "library-description"
+                | "library-version"
+                | "slot-manufacturer"
+                | "slot-description" => {}
+                _ if name.starts_with("x-") => {}
+                _ => return Err(format!("Unknown path attribute: {}", name)),
+            }
+        }
+
+        for attr in query
+            .into_iter()
+            .flat_map(|q| q.split('&'))
+            .filter(|a| !a.is_empty())
+        {
+            let (name, value) = split_attr(attr)?;
+            match name {
+                "pin-source" => {
+                    set_once(&mut parsed.pin_source, name, pct_decode_str(name, value)?)?
+                }
+                "pin-value" => set_once(
+                    &mut parsed.pin_value,
+                    name,
+                    Zeroizing::new(pct_decode_str(name, value)?),
+                )?,
+                "module-name" => {
+                    set_once(&mut parsed.module_name, name, pct_decode_str(name, value)?)?
+                }
+                "module-path" => {
+                    set_once(&mut parsed.module_path, name, pct_decode_str(name, value)?)?
+                }
+                _ if name.starts_with("x-") => {}
+                _ => return Err(format!("Unknown query attribute: {}", name)),
+            }
+        }
+
+        if parsed.pin_source.is_some() && parsed.pin_value.is_some() {
+            return Err(String::from(
+                "pin-source and pin-value are mutually exclusive",
+            ));
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn split_attr(attr: &str) -> Result<(&str, &str), String> {
+    match attr.find('=') {
+        Some(pos) => Ok((&attr[..pos], &attr[pos + 1..])),
+        None => Err(format!("Attribute without value: {}", attr)),
+    }
+}
+
+impl core::str::FromStr for Pkcs11Uri {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pkcs11Uri::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kek_object() {
+        let uri = Pkcs11Uri::parse(
+            "pkcs11:token=My%20token;object=kek-1;id=%01%02%aa;type=secret-key\
+             ?pin-source=file:/etc/token&module-path=/usr/lib/softhsm/libsofthsm2.so",
+        )
+        .unwrap();
+        assert_eq!(uri.token.as_deref(), Some("My token"));
+        assert_eq!(uri.label.as_deref(), Some("kek-1"));
+        assert_eq!(uri.id, Some(vec![0x01, 0x02, 0xaa]));
+        assert_eq!(uri.object_type.as_deref(), Some("secret-key"));
+        assert_eq!(uri.pin_source.as_deref(), Some("file:/etc/token"));
+        assert_eq!(
+            uri.module_path.as_deref(),
+            Some("/usr/lib/softhsm/libsofthsm2.so")
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc7512_examples() {
+        assert_eq!(Pkcs11Uri::parse("pkcs11:").unwrap(), Pkcs11Uri::default());
+        let uri = Pkcs11Uri::parse("pkcs11:object=my-pubkey;type=public").unwrap();
+        assert_eq!(uri.label.as_deref(), Some("my-pubkey"));
+        let uri = Pkcs11Uri::parse(
+            "pkcs11:token=Software%20PKCS%2311%20softtoken;manufacturer=Snake%20Oil,%20Inc.\
+             ?pin-value=the-pin",
+        )
+        .unwrap();
+        assert_eq!(uri.token.as_deref(), Some("Software PKCS#11 softtoken"));
+        assert_eq!(uri.manufacturer.as_deref(), Some("Snake Oil, Inc."));
+        assert_eq!(
+            uri.pin_value.as_deref().map(String::as_str),
+            Some("the-pin")
+        );
+        let uri = Pkcs11Uri::parse("pkcs11:slot-id=3;x-vendor=foo").unwrap();
+        assert_eq!(uri.slot_id, Some(3));
+    }
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let uri = Pkcs11Uri::parse("pkcs11:object=kek-1?pin-value=the-pin").unwrap();
+        let debug = format!("{:?}", uri);
+        assert!(!debug.contains("the-pin"));
+        assert!(debug.contains("pin_value: Some(..redacted..)"));
+        assert!(format!("{:?}", Pkcs11Uri::default()).contains("pin_value: None"));
+    }
+
+    #[test]
+    fn test_errors_do_not_echo_values() {
+        for uri in [
+            "pkcs11:?pin-value=secret%4",
+            "pkcs11:?pin-value=secret%zz",
+            "pkcs11:?pin-value=secret%ff",
+            "pkcs12:?pin-value=secret",
+        ]
+        .iter()
+        {
+            let err = Pkcs11Uri::parse(uri).unwrap_err();
+            assert!(!err.contains("secret"), "{}", err);
+        }
+        assert_eq!(
+            Err(String::from("Invalid percent-encoding in pin-value")),
+            Pkcs11Uri::parse("pkcs11:?pin-value=secret%zz")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Pkcs11Uri::parse("file:/tmp/kek").is_err());
+        assert!(Pkcs11Uri::parse("pkcs11:object=a;object=b").is_err());
+        assert!(Pkcs11Uri::parse("pkcs11:id=%0").is_err());
+        assert!(Pkcs11Uri::parse("pkcs11:id=%zz").is_err());
+        assert!(Pkcs11Uri::parse("pkcs11:bogus=1").is_err());
+        assert!(Pkcs11Uri::parse("pkcs11:?pin-source=a&pin-value=b").is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! PyO3 module `aes_keywrap_rs` with the RFC3394 / RFC5649 functions
+
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, KeyWrapError,
+};
+use alloc::string::ToString;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+fn py_error(e: KeyWrapError) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// Wrap key with the IV defined in RFC3394
+#[pyfunction]
+fn wrap_key<'py>(py: Python<'py>, kek: &[u8], key: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let wrapped = try_wrap_key(kek, key).map_err(py_error)?;
+    Ok(PyBytes::new(py, &wrapped))
+}
+
+/// Unwrap key and Check IV in RFC3394
+#[pyfunction]
+fn unwrap_key<'py>(py: Python<'py>, kek: &[u8], wrapped: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let key = try_unwrap_key(kek, wrapped).map_err(py_error)?;
+    Ok(PyBytes::new(py, key.expose_secret()))
+}
+
+/// Wrap key with pad using padding algorithm (RFC5649)
+#[pyfunction]
+fn wrap_key_with_pad<'py>(
+    py: Python<'py>,
+    kek: &[u8],
+    key: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+    let wrapped = try_wrap_key_with_pad(kek, key).map_err(py_error)?;
+    Ok(PyBytes::new(py, &wrapped))
+}
+
+/// Unwrap key with pad using padding algorithm (RFC5649)
+#[pyfunction]
+fn unwrap_key_with_pad<'py>(
+    py: Python<'py>,
+    kek: &[u8],
+    wrapped: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+    let key = try_unwrap_key_with_pad(kek, wrapped).map_err(py_error)?;
+    Ok(PyBytes::new(py, key.expose_secret()))
+}
+
+#[pymodule]
+fn aes_keywrap_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(wrap_key, m)?)?;
+    m.add_function(wrap_pyfunction!(unwrap_key, m)?)?;
+    m.add_function(wrap_pyfunction!(wrap_key_with_pad, m)?)?;
+    m.add_function(wrap_pyfunction!(unwrap_key_with_pad, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_module() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let m = PyModule::new(py, "aes_keywrap_rs").unwrap();
+            aes_keywrap_rs(&m).unwrap();
+            let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+            let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+            let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+
+            let wrapped: Vec<u8> = m
+                .call_method1("wrap_key", (&kek[..], &plain[..]))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(cipher, wrapped);
+            let key: Vec<u8> = m
+                .call_method1("unwrap_key", (&kek[..], &cipher[..]))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(plain, key);
+
+            let err = m
+                .call_method1("unwrap_key_with_pad", (&kek[..], &cipher[..]))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert_eq!("ValueError: Integrity check failed", err.to_string());
+        });
+    }
+}
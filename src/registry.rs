@@ -0,0 +1,232 @@
+use crate::Algorithm;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+// Algorithm identifiers across ecosystems
+// * RFC 7518 Section 4.4 (JOSE "A128KW" ...; KWP has no JOSE name)
+// * RFC 3394 Section 3 / RFC 5649 Section 6 (OIDs under 2.16.840.1.101.3.4.1)
+// * OpenSSL cipher names ("id-aes128-wrap", "aes128-wrap", "id-aes128-wrap-pad")
+
+struct Entry {
+    algorithm: Algorithm,
+    kek_len: usize,
+    name: &'static str,
+    jose: Option<&'static str>,
+    openssl: &'static str,
+    openssl_alias: &'static str,
+    oid: &'static str,
+}
+
+const REGISTRY: [Entry; 6] = [
+    Entry {
+        algorithm: Algorithm::Kw,
+        kek_len: 16,
+        name: "aes128-kw",
+        jose: Some("A128KW"),
+        openssl: "id-aes128-wrap",
+        openssl_alias: "aes128-wrap",
+        oid: "2.16.840.1.101.3.4.1.5",
+    },
+    Entry {
+        algorithm: Algorithm::Kw,
+        kek_len: 24,
+        name: "aes192-kw",
+        jose: Some("A192KW"),
+        openssl: "id-aes192-wrap",
+        openssl_alias: "aes192-wrap",
+        oid: "2.16.840.1.101.3.4.1.25",
+    },
+    Entry {
+        algorithm: Algorithm::Kw,
+        kek_len: 32,
+        name: "aes256-kw",
+        jose: Some("A256KW"),
+        openssl: "id-aes256-wrap",
+        openssl_alias: "aes256-wrap",
+        oid: "2.16.840.1.101.3.4.1.45",
+    },
+    Entry {
+        algorithm: Algorithm::Kwp,
+        kek_len: 16,
+        name: "aes128-kwp",
+        jose: None,
+        openssl: "id-aes128-wrap-pad",
+        openssl_alias: "aes128-wrap-pad",
+        oid: "2.16.840.1.101.3.4.1.8",
+    },
+    Entry {
+        algorithm: Algorithm::Kwp,
+        kek_len: 24,
+        name: "aes192-kwp",
+        jose: None,
+        openssl: "id-aes192-wrap-pad",
+        openssl_alias: "aes192-wrap-pad",
+        oid: "2.16.840.1.101.3.4.1.28",
+    },
+    Entry {
+        algorithm: Algorithm::Kwp,
+        kek_len: 32,
+        name: "aes256-kwp",
+        jose: None,
+        openssl: "id-aes256-wrap-pad",
+        openssl_alias: "aes256-wrap-pad",
+        oid: "2.16.840.1.101.3.4.1.48",
+    },
+];
+
+/// A key wrap algorithm together with its KEK size
+///
+/// Parses from the crate name ("aes256-kw"), JOSE ("A256KW"), OpenSSL
+/// ("id-aes256-wrap-pad", "aes256-wrap-pad") or dotted OID strings, and
+/// displays as the crate name. Only `new` and parsing build one, so the
+/// pair always has a registry entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AlgorithmId {
+    algorithm: Algorithm,
+    kek_len: usize,
+}
+
+impl AlgorithmId {
+    fn entry(&self) -> &'static Entry {
+        REGISTRY
+            .iter()
+            .find(|e| e.algorithm == self.algorithm && e.kek_len == self.kek_len)
+            .expect("AlgorithmId without a registry entry")
+    }
+
+    pub fn new(algorithm: Algorithm, kek_len: usize) -> Result<AlgorithmId, String> {
+        if !REGISTRY.iter().any(|e| e.algorithm == algorithm) {
+            return Err(format!("No registered identifiers for {}", algorithm));
+        }
+        match kek_len {
+            16 | 24 | 32 => Ok(AlgorithmId { algorithm, kek_len }),
+            _ => Err(format!("kek is not supported: {} bytes", kek_len)),
+        }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// KEK length in bytes (16, 24 or 32)
+    pub fn kek_len(&self) -> usize {
+        self.kek_len
+    }
+
+    /// JOSE "alg" value (RFC 7518), not defined for KWP
+    pub fn jose_name(&self) -> Option<&'static str> {
+        self.entry().jose
+    }
+
+    /// OpenSSL cipher name
+    pub fn openssl_name(&self) -> &'static str {
+        self.entry().openssl
+    }
+
+    /// Dotted object identifier
+    pub fn oid(&self) -> &'static str {
+        self.entry().oid
+    }
+}
+
+impl fmt::Display for AlgorithmId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.entry().name)
+    }
+}
+
+impl FromStr for AlgorithmId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        REGISTRY
+            .iter()
+            .find(|e| {
+                e.name.eq_ignore_ascii_case(s)
+                    || e.jose == Some(s)
+                    || e.openssl.eq_ignore_ascii_case(s)
+                    || e.openssl_alias.eq_ignore_ascii_case(s)
+                    || e.oid == s
+            })
+            .map(|e| AlgorithmId {
+                algorithm: e.algorithm,
+                kek_len: e.kek_len,
+            })
+            .ok_or_else(|| format!("Unknown key wrap algorithm: {}", s))
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::Kw => f.write_str("kw"),
+            Algorithm::Kwp => f.write_str("kwp"),
+            #[cfg(feature = "gcm-siv")]
+            Algorithm::AesGcmSiv => f.write_str("aes-gcm-siv"),
+            #[cfg(feature = "gcm")]
+            Algorithm::AesGcm => f.write_str("aes-gcm"),
+            #[cfg(feature = "chacha")]
+            Algorithm::ChaCha20Poly1305 => f.write_str("chacha20-poly1305"),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "kw" | "rfc3394" => Ok(Algorithm::Kw),
+            "kwp" | "rfc5649" => Ok(Algorithm::Kwp),
+            #[cfg(feature = "gcm-siv")]
+            "aes-gcm-siv" | "rfc8452" => Ok(Algorithm::AesGcmSiv),
+            #[cfg(feature = "gcm")]
+            "aes-gcm" => Ok(Algorithm::AesGcm),
+            #[cfg(feature = "chacha")]
+            "chacha20-poly1305" | "rfc8439" => Ok(Algorithm::ChaCha20Poly1305),
+            _ => Err(format!("Unknown key wrap algorithm: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ecosystem_names() {
+        let a256kw = AlgorithmId::new(Algorithm::Kw, 32).unwrap();
+        assert_eq!(a256kw, "A256KW".parse().unwrap());
+        assert_eq!(a256kw, "id-aes256-wrap".parse().unwrap());
+        assert_eq!(a256kw, "aes256-wrap".parse().unwrap());
+        assert_eq!(a256kw, "2.16.840.1.101.3.4.1.45".parse().unwrap());
+        assert_eq!(a256kw, "AES256-KW".parse().unwrap());
+
+        let a128kwp = AlgorithmId::new(Algorithm::Kwp, 16).unwrap();
+        assert_eq!(a128kwp, "id-aes128-wrap-pad".parse().unwrap());
+        assert_eq!(a128kwp, "2.16.840.1.101.3.4.1.8".parse().unwrap());
+        assert_eq!(None, a128kwp.jose_name());
+        assert_eq!(Algorithm::Kwp, a128kwp.algorithm());
+        assert_eq!(16, a128kwp.kek_len());
+
+        assert!("A256GCMKW".parse::<AlgorithmId>().is_err());
+        assert!(AlgorithmId::new(Algorithm::Kw, 20).is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for e in REGISTRY.iter() {
+            let id: AlgorithmId = e.name.parse().unwrap();
+            assert_eq!(e.name, id.to_string());
+            assert_eq!(id, id.openssl_name().parse().unwrap());
+            assert_eq!(id, id.oid().parse().unwrap());
+        }
+        for algorithm in [Algorithm::Kw, Algorithm::Kwp].iter() {
+            assert_eq!(*algorithm, algorithm.to_string().parse().unwrap());
+        }
+        assert_eq!(Algorithm::Kwp, "RFC5649".parse().unwrap());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{Algorithm, KeyWrapError, KeyWrapper, UnwrappedKey};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Retry, deadline and circuit-breaker settings for `ResilientKeyWrapper`
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on each further retry
+    pub base_delay: Duration,
+    /// Upper bound for a single backoff
+    pub max_delay: Duration,
+    /// Give up once the call as a whole would run past this
+    pub deadline: Option<Duration>,
+    /// Consecutive transient failures that open the circuit
+    pub failure_threshold: u32,
+    /// How long an open circuit rejects calls
+    pub cooldown: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+            deadline: Some(Duration::from_secs(10)),
+            failure_threshold: 10,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    rng: u64,
+}
+
+/// `KeyWrapper` decorator adding retries with jittered exponential backoff,
+/// an overall deadline and a circuit breaker around a remote provider
+///
+/// Only errors accepted by the `is_transient` classifier (typically
+/// `KeyWrapError::ProviderUnavailable`) are retried or counted by the
+/// breaker; integrity failures and other permanent errors are returned
+/// straight away. Once retries, the deadline or the breaker give up, the
+/// last error is returned; an open circuit fails with `ProviderUnavailable`.
+/// Backoff sleeps on the calling thread.
+pub struct ResilientKeyWrapper<W: KeyWrapper> {
+    inner: W,
+    policy: RetryPolicy,
+    is_transient: Box<dyn Fn(&KeyWrapError) -> bool + Send + Sync>,
+    state: Mutex<BreakerState>,
+}
+
+impl<W: KeyWrapper> ResilientKeyWrapper<W> {
+    pub fn new<F>(inner: W, policy: RetryPolicy, is_transient: F) -> ResilientKeyWrapper<W>
+    where
+        F: Fn(&KeyWrapError) -> bool + Send + Sync + 'static,
+    {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        ResilientKeyWrapper {
+            inner,
+            policy,
+            is_transient: Box::new(is_transient),
+            state: Mutex::new(BreakerState {
+                consecutive_failures: 0,
+                open_until: None,
+                rng: seed | 1,
+            }),
+        }
+    }
+
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    // full jitter: uniform in [0, min(max_delay, base_delay * 2^attempt)]
+    fn backoff(&self, state: &mut BreakerState, attempt: u32) -> Duration {
+        let ceiling = self
+            .policy
+            .base_delay
+            .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+            .unwrap_or(self.policy.max_delay)
+            .min(self.policy.max_delay);
+        state.rng ^= state.rng << 13;
+        state.rng ^= state.rng >> 7;
+        state.rng ^= state.rng << 17;
+        let nanos = ceiling.as_nanos() as u64;
+        Duration::from_nanos(if nanos == 0 {
+            0
+        } else {
+            state.rng % (nanos + 1)
+        })
+    }
+
+    fn call<T, F>(&self, op: F) -> Result<T, KeyWrapError>
+    where
+        F: Fn(&W) -> Result<T, KeyWrapError>,
+    {
+        let start = Instant::now();
+        {
+            let mut state = self.state.lock().unwrap();
+            match state.open_until {
+                Some(until) if Instant::now() < until => {
+                    return Err(KeyWrapError::ProviderUnavailable)
+                }
+                Some(_) => state.open_until = None,
+                None => {}
+            }
+        }
+
+        let mut attempt = 0;
+        loop {
+            let err = match op(&self.inner) {
+                Ok(v) => {
+                    self.state.lock().unwrap().consecutive_failures = 0;
+                    return Ok(v);
+                }
+                Err(e) if !(self.is_transient)(&e) => return Err(e),
+                Err(e) => e,
+            };
+
+            let delay = {
+                let mut state = self.state.lock().unwrap();
+                state.consecutive_failures += 1;
+                if state.consecutive_failures >= self.policy.failure_threshold {
+                    state.open_until = Some(Instant::now() + self.policy.cooldown);
+                    return Err(err);
+                }
+                self.backoff(&mut state, attempt)
+            };
+
+            if attempt >= self.policy.max_retries {
+                return Err(err);
+            }
+            if let Some(deadline) = self.policy.deadline {
+                if start.elapsed() + delay > deadline {
+                    return Err(err);
+                }
+            }
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+impl<W: KeyWrapper> KeyWrapper for ResilientKeyWrapper<W> {
+    fn algorithm(&self) -> Algorithm {
+        self.inner.algorithm()
+    }
+
+    fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        self.call(|w| w.wrap(plaintext))
+    }
+
+    fn unwrap(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        self.call(|w| w.unwrap(wrapped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockKeyWrapper;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+            deadline: Some(Duration::from_secs(1)),
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+
+    fn resilient() -> ResilientKeyWrapper<MockKeyWrapper> {
+        ResilientKeyWrapper::new(MockKeyWrapper::new(Algorithm::Kw), policy(), |e| {
+            *e == KeyWrapError::ProviderUnavailable
+        })
+    }
+
+    #[test]
+    fn test_retries_transient_failures() {
+        let wrapper = resilient();
+        wrapper.inner().fail_next_wrap_with_timeout();
+        wrapper.inner().fail_next_wrap_with_timeout();
+        assert!(wrapper.wrap(b"key").is_ok());
+        assert_eq!(3, wrapper.inner().calls().len());
+
+        for _ in 0..3 {
+            wrapper.inner().fail_next_wrap_with_timeout();
+        }
+        assert_eq!(Err(KeyWrapError::ProviderUnavailable), wrapper.wrap(b"key"));
+        assert_eq!(6, wrapper.inner().calls().len());
+    }
+
+    #[test]
+    fn test_permanent_failures_are_not_retried() {
+        let wrapper = resilient();
+        wrapper.inner().fail_next_unwrap_with_integrity_error();
+        assert_eq!(
+            Some(KeyWrapError::IntegrityCheckFailed),
+            wrapper.unwrap(b"blob").err()
+        );
+        assert_eq!(1, wrapper.inner().calls().len());
+    }
+
+    #[test]
+    fn test_circuit_breaker_opens() {
+        let wrapper = resilient();
+        for _ in 0..5 {
+            wrapper.inner().fail_next_unwrap_with_timeout();
+        }
+        assert!(wrapper.unwrap(b"blob").is_err());
+        assert!(wrapper.unwrap(b"blob").is_err());
+        assert_eq!(
+            Some(KeyWrapError::ProviderUnavailable),
+            wrapper.unwrap(b"blob").err()
+        );
+        assert_eq!(5, wrapper.inner().calls().len());
+    }
+}
@@ -0,0 +1,125 @@
+// AES block function from the RustCrypto `aes` crate (`aes` feature)
+//
+// `aes` picks its implementation at runtime: AES-NI on x86/x86_64 and the
+// ARMv8 Cryptography Extensions on aarch64 when the CPU reports them (via
+// `cpufeatures`), otherwise its constant-time fixsliced software AES. The
+// detection runs once per process and needs no target-cpu flags at build
+// time, unlike crypto2, which only uses AES-NI when compiled with it.
+//
+// The types mirror crypto2's `blockmode::AesXxxEcb` API (new/encrypt/decrypt)
+// like `soft_aes`. Blocks are handed to `aes` up to `PAR_BLOCKS` at a time so
+// its pipelined backends can interleave them (see `multilane`).
+
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::Block;
+use zeroize::Zeroize;
+
+const BLOCK_LEN: usize = 16;
+const PAR_BLOCKS: usize = 8;
+
+macro_rules! impl_rustcrypto_ecb {
+    ($name:tt, $cipher:ty, $key_len:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            cipher: $cipher,
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize = $key_len;
+            pub const BLOCK_LEN: usize = BLOCK_LEN;
+
+            pub fn new(key: &[u8]) -> Self {
+                assert_eq!(key.len(), Self::KEY_LEN);
+
+                let cipher = <$cipher>::new_from_slice(key).expect("key length checked above");
+                Self { cipher }
+            }
+
+            pub fn encrypt(&mut self, blocks: &mut [u8]) {
+                assert_eq!(blocks.len() % Self::BLOCK_LEN, 0);
+
+                let mut par = [Block::default(); PAR_BLOCKS];
+                for chunk in blocks.chunks_mut(BLOCK_LEN * PAR_BLOCKS) {
+                    let k = chunk.len() / BLOCK_LEN;
+                    for (b, c) in par.iter_mut().zip(chunk.chunks(BLOCK_LEN)) {
+                        *b = Block::clone_from_slice(c);
+                    }
+                    self.cipher.encrypt_blocks(&mut par[..k]);
+                    for (b, c) in par.iter().zip(chunk.chunks_mut(BLOCK_LEN)) {
+                        c.copy_from_slice(b);
+                    }
+                }
+                for b in par.iter_mut() {
+                    b[..].zeroize();
+                }
+            }
+
+            pub fn decrypt(&mut self, blocks: &mut [u8]) {
+                assert_eq!(blocks.len() % Self::BLOCK_LEN, 0);
+
+                let mut par = [Block::default(); PAR_BLOCKS];
+                for chunk in blocks.chunks_mut(BLOCK_LEN * PAR_BLOCKS) {
+                    let k = chunk.len() / BLOCK_LEN;
+                    for (b, c) in par.iter_mut().zip(chunk.chunks(BLOCK_LEN)) {
+                        *b = Block::clone_from_slice(c);
+                    }
+                    self.cipher.decrypt_blocks(&mut par[..k]);
+                    for (b, c) in par.iter().zip(chunk.chunks_mut(BLOCK_LEN)) {
+                        c.copy_from_slice(b);
+                    }
+                }
+                for b in par.iter_mut() {
+                    b[..].zeroize();
+                }
+            }
+        }
+    };
+}
+
+impl_rustcrypto_ecb!(Aes128Ecb, aes::Aes128, 16);
+impl_rustcrypto_ecb!(Aes192Ecb, aes::Aes192, 24);
+impl_rustcrypto_ecb!(Aes256Ecb, aes::Aes256, 32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FIPS 197 appendix C
+    #[test]
+    fn test_fips197_vectors() {
+        let plain = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        for (key, cipher) in [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ]
+        .iter()
+        {
+            let key = hex::decode(key).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            // more blocks than one parallel batch
+            let mut blocks = plain.repeat(PAR_BLOCKS + 3);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).encrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).encrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key).encrypt(&mut blocks),
+            }
+            assert_eq!(cipher.repeat(PAR_BLOCKS + 3), blocks);
+            match key.len() {
+                16 => Aes128Ecb::new(&key).decrypt(&mut blocks),
+                24 => Aes192Ecb::new(&key).decrypt(&mut blocks),
+                _ => Aes256Ecb::new(&key).decrypt(&mut blocks),
+            }
+            assert_eq!(plain.repeat(PAR_BLOCKS + 3), blocks);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    check_kw_block, check_kw_plaintext_len, check_kw_wrapped_len, check_kwp_block,
+    check_kwp_plaintext_len, check_kwp_wrapped_len, unwrap_in_place, wrap_in_place, KeyWrapError,
+    IV_3394, IV_5649,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+use zeroize::Zeroize;
+
+/// Reusable working memory for wrap/unwrap without allocator traffic
+///
+/// The buffer is allocated once, sized for the largest plaintext the caller
+/// expects, and every result is returned as a slice borrowed from it. Key
+/// material left in the buffer is cleared when the next call starts, when
+/// an unwrap fails, and on drop.
+pub struct WrapScratch {
+    buf: Vec<u8>,
+}
+
+impl WrapScratch {
+    /// Scratch space for plaintexts of up to `max_plaintext_len` bytes
+    pub fn new(max_plaintext_len: usize) -> WrapScratch {
+        // ICV semiblock plus at most 7 octets of RFC5649 padding
+        WrapScratch {
+            buf: vec![0u8; max_plaintext_len + 15],
+        }
+    }
+
+    /// Largest plaintext this scratch can hold
+    pub fn max_plaintext_len(&self) -> usize {
+        self.buf.len() - 15
+    }
+
+    /// Overwrite the whole buffer with zeros
+    pub fn clear(&mut self) {
+        self.buf[..].zeroize();
+    }
+
+    fn reserve(&mut self, len: usize) -> Result<&mut [u8], KeyWrapError> {
+        if len > self.buf.len() {
+            return Err(KeyWrapError::BufferTooSmall(len));
+        }
+        self.clear();
+        Ok(&mut self.buf[..len])
+    }
+
+    /// Wrap key with the IV defined in RFC3394
+    pub fn wrap_key(&mut self, kek: &[u8], plaintext: &[u8]) -> Result<&[u8], KeyWrapError> {
+        check_kw_plaintext_len(plaintext.len())?;
+
+        let buf = self.reserve(plaintext.len() + 8)?;
+        buf[..8].copy_from_slice(&IV_3394);
+        buf[8..].copy_from_slice(plaintext);
+        if let Err(e) = wrap_in_place(kek, buf) {
+            self.clear();
+            return Err(e);
+        }
+        Ok(&self.buf[..plaintext.len() + 8])
+    }
+
+    /// Unwrap key and Check IV in RFC3394
+    pub fn unwrap_key(&mut self, kek: &[u8], wrapped: &[u8]) -> Result<&[u8], KeyWrapError> {
+        check_kw_wrapped_len(wrapped.len())?;
+
+        let buf = self.reserve(wrapped.len())?;
+        buf.copy_from_slice(wrapped);
+        if let Err(e) = unwrap_in_place(kek, buf).and_then(|_| check_kw_block(buf)) {
+            self.clear();
+            return Err(e);
+        }
+        Ok(&self.buf[8..wrapped.len()])
+    }
+
+    /// Wrap key with pad using padding algorithm (RFC5649)
+    pub fn wrap_key_with_pad(
+        &mut self,
+        kek: &[u8],
+        plaintext: &[u8],
+    ) -> Result<&[u8], KeyWrapError> {
+        check_kwp_plaintext_len(plaintext.len())?;
+
+        let wrapped_len = plaintext.len().div_ceil(8) * 8 + 8;
+        let buf = self.reserve(wrapped_len)?;
+        buf[..4].copy_from_slice(&IV_5649);
+        buf[4..8].copy_from_slice(&(plaintext.len() as u32).to_be_bytes());
+        buf[8..8 + plaintext.len()].copy_from_slice(plaintext);
+        if let Err(e) = wrap_in_place(kek, buf) {
+            self.clear();
+            return Err(e);
+        }
+        Ok(&self.buf[..wrapped_len])
+    }
+
+    /// Unwrap key with pad using padding algorithm (RFC5649)
+    pub fn unwrap_key_with_pad(
+        &mut self,
+        kek: &[u8],
+        wrapped: &[u8],
+    ) -> Result<&[u8], KeyWrapError> {
+        check_kwp_wrapped_len(wrapped.len())?;
+
+        let buf = self.reserve(wrapped.len())?;
+        buf.copy_from_slice(wrapped);
+        match unwrap_in_place(kek, buf).and_then(|_| check_kwp_block(buf)) {
+            Ok(key_len) => Ok(&self.buf[8..8 + key_len]),
+            Err(e) => {
+                self.clear();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for WrapScratch {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aes_wrap_key, aes_wrap_key_with_pad};
+
+    #[test]
+    fn test_scratch_matches_allocating_api() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+            .unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F")
+            .unwrap();
+        let mut scratch = WrapScratch::new(64);
+        for len in [16, 24, 32].iter() {
+            let wrapped = aes_wrap_key(&kek, &plain[..*len]).unwrap();
+            assert_eq!(wrapped, scratch.wrap_key(&kek, &plain[..*len]).unwrap());
+            assert_eq!(&plain[..*len], scratch.unwrap_key(&kek, &wrapped).unwrap());
+        }
+        for len in 1..33 {
+            let wrapped = aes_wrap_key_with_pad(&kek, &plain[..len]).unwrap();
+            assert_eq!(
+                wrapped,
+                scratch.wrap_key_with_pad(&kek, &plain[..len]).unwrap()
+            );
+            assert_eq!(
+                &plain[..len],
+                scratch.unwrap_key_with_pad(&kek, &wrapped).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_scratch_rfc5649_vector() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let cipher =
+            hex::decode("138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A")
+                .unwrap();
+        let plain = hex::decode("C37B7E6492584340BED12207808941155068F738").unwrap();
+        let mut scratch = WrapScratch::new(20);
+        assert_eq!(cipher, scratch.wrap_key_with_pad(&kek, &plain).unwrap());
+        assert_eq!(plain, scratch.unwrap_key_with_pad(&kek, &cipher).unwrap());
+    }
+
+    #[test]
+    fn test_scratch_errors_and_clearing() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let mut scratch = WrapScratch::new(16);
+        assert_eq!(
+            Err(KeyWrapError::BufferTooSmall(32)),
+            scratch.wrap_key(&kek, &[0u8; 24])
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidPlaintextLength(12)),
+            scratch.wrap_key(&kek, &[0u8; 12])
+        );
+        assert_eq!(
+            Err(KeyWrapError::InvalidCiphertextLength(16)),
+            scratch.unwrap_key(&kek, &[0u8; 16])
+        );
+
+        let mut wrapped = scratch.wrap_key(&kek, &[0x55u8; 16]).unwrap().to_vec();
+        wrapped[0] ^= 1;
+        assert_eq!(
+            Err(KeyWrapError::IntegrityCheckFailed),
+            scratch.unwrap_key(&kek, &wrapped)
+        );
+        assert!(scratch.buf.iter().all(|b| *b == 0));
+
+        // same reasons as the allocating API, e.g. non-zero padding
+        let wrapped = crate::aes_wrap_key_with_pad(&kek, &[0x55u8; 5]).unwrap();
+        for v in crate::kwp_negative_vectors(&kek, &wrapped).unwrap() {
+            assert_eq!(
+                crate::try_unwrap_key_with_pad(&kek, &v.wrapped).map(|_| ()),
+                scratch.unwrap_key_with_pad(&kek, &v.wrapped).map(|_| ())
+            );
+            assert!(scratch.buf.iter().all(|b| *b == 0));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, KeyWrapError,
+    UnwrappedKey,
+};
+use alloc::vec::Vec;
+use secrecy::{ExposeSecret, SecretBox};
+use zeroize::Zeroize;
+
+/// Key wrap methods on KEKs held in `secrecy` containers
+///
+/// Implemented for `SecretSlice<u8>`, `SecretBox<[u8; N]>` and
+/// `SecretBox<Vec<u8>>`, so the KEK bytes are only exposed inside the
+/// wrap/unwrap call.
+///
+/// ```ignore
+/// let kek: SecretSlice<u8> = load_kek().into();
+/// let wrapped = kek.wrap_key_with_pad(&dek)?;
+/// ```
+pub trait SecretKek {
+    /// Wrap key with the IV defined in RFC3394
+    fn wrap_key(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError>;
+
+    /// Unwrap key and Check IV in RFC3394
+    fn unwrap_key(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError>;
+
+    /// Wrap key with pad using padding algorithm (RFC5649)
+    fn wrap_key_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError>;
+
+    /// Unwrap key with pad using padding algorithm (RFC5649)
+    fn unwrap_key_with_pad(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError>;
+}
+
+impl<S: Zeroize + AsRef<[u8]> + ?Sized> SecretKek for SecretBox<S> {
+    fn wrap_key(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        try_wrap_key(self.expose_secret().as_ref(), plaintext)
+    }
+
+    fn unwrap_key(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        try_unwrap_key(self.expose_secret().as_ref(), wrapped)
+    }
+
+    fn wrap_key_with_pad(&self, plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        try_wrap_key_with_pad(self.expose_secret().as_ref(), plaintext)
+    }
+
+    fn unwrap_key_with_pad(&self, wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        try_unwrap_key_with_pad(self.expose_secret().as_ref(), wrapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::SecretSlice;
+
+    #[test]
+    fn test_secret_kek() {
+        let kek: SecretSlice<u8> = hex::decode("000102030405060708090A0B0C0D0E0F")
+            .unwrap()
+            .into();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        assert_eq!(cipher, kek.wrap_key(&plain).unwrap());
+        assert_eq!(&plain[..], kek.unwrap_key(&cipher).unwrap().expose_secret());
+
+        let kek = SecretBox::new(Box::new([0x5au8; 32]));
+        let wrapped = kek.wrap_key_with_pad(b"short").unwrap();
+        assert_eq!(
+            b"short",
+            kek.unwrap_key_with_pad(&wrapped).unwrap().expose_secret()
+        );
+
+        let kek: SecretSlice<u8> = vec![0u8; 20].into();
+        assert_eq!(
+            Some(KeyWrapError::InvalidKekLength(20)),
+            kek.wrap_key(&plain).err()
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{Kek, KeyWrapError, UnwrappedKey};
+use alloc::string::String;
+use alloc::vec::Vec;
+use kisaseed::SEED;
+
+// SEED key wrap (RFC 4010, `seed-kw` feature)
+//
+// RFC 4010 runs the RFC3394 algorithm with the 128-bit SEED block cipher,
+// as required by the Korean PKI CMS profiles. SEED only has 128-bit keys,
+// so unlike the AES and Camellia variants there is a single KEK size.
+
+/// id-npki-app-cmsSeed-wrap (RFC 4010 Section 3)
+pub const ID_NPKI_APP_CMS_SEED_WRAP: &str = "1.2.410.200004.7.1.1.1";
+
+/// Wrap key with SEED and the IV defined in RFC3394 (RFC 4010)
+pub fn try_seed_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    Kek::<SEED>::new_from_slice(kek)?.wrap(plaintext)
+}
+
+/// Unwrap key with SEED and Check IV (RFC 4010)
+pub fn try_seed_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    Kek::<SEED>::new_from_slice(kek)?.unwrap(wrapped)
+}
+
+/// Wrap key with SEED and the IV defined in RFC3394 (RFC 4010)
+pub fn seed_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_seed_wrap_key(kek, plaintext)?)
+}
+
+/// Unwrap key with SEED and Check IV (RFC 4010)
+pub fn seed_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_seed_unwrap_key(kek, wrapped)?.expose_secret().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4010 Section 4
+    #[test]
+    fn test_rfc4010_vector() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let cipher = hex::decode("BF71F77138B5AFEA05232A8DAD54024E812DC8DD7D132559").unwrap();
+        assert_eq!(cipher, seed_wrap_key(&kek, &plain).unwrap());
+        assert_eq!(plain, seed_unwrap_key(&kek, &cipher).unwrap());
+        assert_eq!(
+            KeyWrapError::InvalidKekLength(24),
+            try_seed_wrap_key(&[0u8; 24], &plain).unwrap_err()
+        );
+    }
+}
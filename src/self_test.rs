@@ -0,0 +1,297 @@
+use crate::{Aes128Kw, Aes192Kw, Aes256Kw, KeyWrapError, IV_3394, IV_5649};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+// Power-up known-answer tests (`self-test` feature)
+//
+// The RFC3394 section 4 and RFC5649 section 6 vectors are run through both
+// the allocating (`aes_*`) and the in-place (`try_*`) implementations,
+// followed by a tampered-input check. With the feature enabled the first
+// wrap or unwrap runs them, and a failure refuses every later operation
+// with `KeyWrapError::SelfTestFailed`. `run_self_tests` can be called
+// explicitly before operational use; its failure is sticky too.
+//
+// The KATs call the `Aes*Kw` functions behind the gate, so they can run
+// while it is closed.
+
+struct Kat {
+    name: &'static str,
+    kek: &'static str,
+    plain: &'static str,
+    cipher: &'static str,
+    pad: bool,
+}
+
+const KATS: [Kat; 8] = [
+    Kat {
+        name: "RFC3394 4.1 (128-bit KEK, 128-bit key)",
+        kek: "000102030405060708090A0B0C0D0E0F",
+        plain: "00112233445566778899AABBCCDDEEFF",
+        cipher: "1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5",
+        pad: false,
+    },
+    Kat {
+        name: "RFC3394 4.2 (192-bit KEK, 128-bit key)",
+        kek: "000102030405060708090A0B0C0D0E0F1011121314151617",
+        plain: "00112233445566778899AABBCCDDEEFF",
+        cipher: "96778B25AE6CA435F92B5B97C050AED2468AB8A17AD84E5D",
+        pad: false,
+    },
+    Kat {
+        name: "RFC3394 4.3 (256-bit KEK, 128-bit key)",
+        kek: "000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F",
+        plain: "00112233445566778899AABBCCDDEEFF",
+        cipher: "64E8C3F9CE0F5BA263E9777905818A2A93C8191E7D6E8AE7",
+        pad: false,
+    },
+    Kat {
+        name: "RFC3394 4.4 (192-bit KEK, 192-bit key)",
+        kek: "000102030405060708090A0B0C0D0E0F1011121314151617",
+        plain: "00112233445566778899AABBCCDDEEFF0001020304050607",
+        cipher: "031D33264E15D33268F24EC260743EDCE1C6C7DDEE725A936BA814915C6762D2",
+        pad: false,
+    },
+    Kat {
+        name: "RFC3394 4.5 (256-bit KEK, 192-bit key)",
+        kek: "000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F",
+        plain: "00112233445566778899AABBCCDDEEFF0001020304050607",
+        cipher: "A8F9BC1612C68B3FF6E6F4FBE30E71E4769C8B80A32CB8958CD5D17D6B254DA1",
+        pad: false,
+    },
+    Kat {
+        name: "RFC3394 4.6 (256-bit KEK, 256-bit key)",
+        kek: "000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F",
+        plain: "00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F",
+        cipher: "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21",
+        pad: false,
+    },
+    Kat {
+        name: "RFC5649 6 (192-bit KEK, 20 octets)",
+        kek: "5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8",
+        plain: "C37B7E6492584340BED12207808941155068F738",
+        cipher: "138BDEAA9B8FA7FC61F97742E72248EE5AE6AE5360D1AE6A5F54F373FA543B6A",
+        pad: true,
+    },
+    Kat {
+        name: "RFC5649 6 (192-bit KEK, 7 octets)",
+        kek: "5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8",
+        plain: "466F7250617369",
+        cipher: "AFBEB0F07DFBF5419200F2CCB50BB24F",
+        pad: true,
+    },
+];
+
+/// Outcome of a successful self-test run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Names of the known-answer tests that passed, in the order they ran
+    pub passed: Vec<&'static str>,
+}
+
+/// The first known-answer test that failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfTestError {
+    /// Name of the failing test
+    pub test: &'static str,
+    /// What didn't match
+    pub reason: String,
+}
+
+impl fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Self-test {} failed: {}", self.test, self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SelfTestError {}
+
+// dispatch on KEK size without going through the gated free functions
+macro_rules! by_kek_len {
+    ($kek:expr, $f:ident($($arg:expr),*)) => {
+        match $kek.len() {
+            16 => Aes128Kw::$f($($arg),*),
+            24 => Aes192Kw::$f($($arg),*),
+            _ => Aes256Kw::$f($($arg),*),
+        }
+    };
+}
+
+fn run_kat(kat: &Kat) -> Result<(), String> {
+    let kek = hex::decode(kat.kek).map_err(|e| e.to_string())?;
+    let plain = hex::decode(kat.plain).map_err(|e| e.to_string())?;
+    let cipher = hex::decode(kat.cipher).map_err(|e| e.to_string())?;
+
+    // allocating implementation
+    let wrapped = if kat.pad {
+        by_kek_len!(kek, wrap_key_with_pad(&kek, &plain))?
+    } else {
+        by_kek_len!(kek, wrap_key_and_iv(&kek, &plain, &IV_3394))?
+    };
+    if wrapped != cipher {
+        return Err(String::from("wrap output mismatch"));
+    }
+    let unwrapped = if kat.pad {
+        by_kek_len!(kek, unwrap_key_with_pad(&kek, &cipher))?
+    } else {
+        let (key, iv) = by_kek_len!(kek, unwrap_key_and_iv(&kek, &cipher))?;
+        if iv[..] != IV_3394[..] {
+            return Err(String::from("unwrap IV mismatch"));
+        }
+        key
+    };
+    if unwrapped != plain {
+        return Err(String::from("unwrap output mismatch"));
+    }
+
+    // in-place implementation, over ICV/AIV | (padded) plaintext
+    let mut buf: Vec<u8> = Vec::with_capacity(cipher.len());
+    if kat.pad {
+        buf.extend_from_slice(&IV_5649);
+        buf.extend_from_slice(&(plain.len() as u32).to_be_bytes());
+    } else {
+        buf.extend_from_slice(&IV_3394);
+    }
+    buf.extend_from_slice(&plain);
+    buf.resize(cipher.len(), 0);
+    let input = buf.clone();
+    by_kek_len!(kek, wrap_in_place(&kek, &mut buf))?;
+    if buf != cipher {
+        return Err(String::from("in-place wrap output mismatch"));
+    }
+    by_kek_len!(kek, unwrap_in_place(&kek, &mut buf))?;
+    if buf != input {
+        return Err(String::from("in-place unwrap output mismatch"));
+    }
+
+    // a flipped bit must not pass the integrity check
+    let mut tampered = cipher;
+    tampered[0] ^= 0x01;
+    let accepted = if kat.pad {
+        by_kek_len!(kek, unwrap_key_with_pad(&kek, &tampered)).is_ok()
+    } else {
+        by_kek_len!(kek, unwrap_key_and_iv(&kek, &tampered))
+            .map(|(_, iv)| iv[..] == IV_3394[..])
+            .unwrap_or(false)
+    };
+    if accepted {
+        return Err(String::from("tampered input accepted"));
+    }
+
+    Ok(())
+}
+
+fn run_kats(kats: &[Kat]) -> Result<SelfTestReport, SelfTestError> {
+    let mut passed = Vec::with_capacity(kats.len());
+    for kat in kats {
+        run_kat(kat).map_err(|reason| SelfTestError {
+            test: kat.name,
+            reason,
+        })?;
+        passed.push(kat.name);
+    }
+    Ok(SelfTestReport { passed })
+}
+
+/// Run the RFC3394 / RFC5649 known-answer tests
+///
+/// With the `self-test` feature a failure also makes every later wrap and
+/// unwrap fail with `KeyWrapError::SelfTestFailed`.
+pub fn run_self_tests() -> Result<SelfTestReport, SelfTestError> {
+    let result = run_kats(&KATS);
+    #[cfg(feature = "self-test")]
+    state::record(result.is_ok());
+    result
+}
+
+#[cfg(feature = "self-test")]
+mod state {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNTESTED: u8 = 0;
+    const PASSED: u8 = 1;
+    const FAILED: u8 = 2;
+
+    static STATE: AtomicU8 = AtomicU8::new(UNTESTED);
+
+    // a failure is never overwritten by a later pass
+    pub(super) fn record(passed: bool) {
+        if passed {
+            let _ = STATE.compare_exchange(UNTESTED, PASSED, Ordering::AcqRel, Ordering::Acquire);
+        } else {
+            STATE.store(FAILED, Ordering::Release);
+        }
+    }
+
+    pub(super) fn passed() -> bool {
+        if STATE.load(Ordering::Acquire) == UNTESTED {
+            let _ = super::run_self_tests();
+        }
+        STATE.load(Ordering::Acquire) == PASSED
+    }
+}
+
+// Gate in front of every public wrap / unwrap entry point
+#[cfg(feature = "self-test")]
+pub(crate) fn power_up() -> Result<(), KeyWrapError> {
+    if state::passed() {
+        Ok(())
+    } else {
+        Err(KeyWrapError::SelfTestFailed)
+    }
+}
+
+#[cfg(not(feature = "self-test"))]
+#[inline(always)]
+pub(crate) fn power_up() -> Result<(), KeyWrapError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_self_tests() {
+        let report = run_self_tests().unwrap();
+        assert_eq!(KATS.len(), report.passed.len());
+        assert_eq!("RFC3394 4.1 (128-bit KEK, 128-bit key)", report.passed[0]);
+    }
+
+    #[test]
+    fn test_failing_kat_is_reported() {
+        let bad = [Kat {
+            name: "bad",
+            cipher: "1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE6",
+            ..KATS[0]
+        }];
+        let err = run_kats(&bad).unwrap_err();
+        assert_eq!("bad", err.test);
+        assert_eq!("wrap output mismatch", err.reason);
+        assert_eq!(
+            "Self-test bad failed: wrap output mismatch",
+            err.to_string()
+        );
+    }
+
+    #[cfg(feature = "self-test")]
+    #[test]
+    fn test_power_up_gate() {
+        assert_eq!(Ok(()), power_up());
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let wrapped = crate::try_wrap_key(&kek, &plain).unwrap();
+        assert_eq!(
+            &plain[..],
+            crate::try_unwrap_key(&kek, &wrapped)
+                .unwrap()
+                .expose_secret()
+        );
+        assert_eq!(
+            wrapped,
+            crate::Aes128Kw::aes_wrap_key_and_iv(&kek, &plain, &IV_3394).unwrap()
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use zeroize::Zeroize;
+
+// Vendored AES block function for the `self-contained` feature
+// * FIPS 197 "Advanced Encryption Standard (AES)"
+//   https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.197.pdf
+//
+// There are no lookup tables: the S-box is computed as the GF(2^8) inverse
+// (x^254) followed by the affine transform, and every field multiplication
+// is done with masks instead of branches, so the running time doesn't depend
+// on key or data bytes. It is much slower than crypto2's AES-NI path but
+// small enough to review in one sitting.
+//
+// The types mirror crypto2's `blockmode::AesXxxEcb` API (new/encrypt/decrypt)
+// so the key wrap code doesn't need to know which one it got. The expanded
+// round keys are zeroized on drop.
+
+const BLOCK_LEN: usize = 16;
+
+#[inline(always)]
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut p = 0u8;
+    for _ in 0..8 {
+        p ^= a & 0u8.wrapping_sub(b & 1);
+        let hi = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (hi & 0x1b);
+        b >>= 1;
+    }
+    p
+}
+
+#[inline(always)]
+fn xtime(a: u8) -> u8 {
+    (a << 1) ^ (0u8.wrapping_sub(a >> 7) & 0x1b)
+}
+
+// x^254 == x^-1 in GF(2^8) (and 0 -> 0)
+#[inline(always)]
+fn gf_inv(x: u8) -> u8 {
+    let x2 = gf_mul(x, x);
+    let x3 = gf_mul(x2, x);
+    let x6 = gf_mul(x3, x3);
+    let x12 = gf_mul(x6, x6);
+    let x15 = gf_mul(x12, x3);
+    let x30 = gf_mul(x15, x15);
+    let x60 = gf_mul(x30, x30);
+    let x120 = gf_mul(x60, x60);
+    let x240 = gf_mul(x120, x120);
+    let x252 = gf_mul(x240, x12);
+    gf_mul(x252, x2)
+}
+
+#[inline(always)]
+fn sub_byte(x: u8) -> u8 {
+    let b = gf_inv(x);
+    b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63
+}
+
+#[inline(always)]
+fn inv_sub_byte(x: u8) -> u8 {
+    gf_inv(x.rotate_left(1) ^ x.rotate_left(3) ^ x.rotate_left(6) ^ 0x05)
+}
+
+// The state is kept in input order: byte (row r, column c) is state[4 * c + r]
+fn add_round_key(state: &mut [u8], round_key: &[u8]) {
+    for (s, k) in state.iter_mut().zip(round_key) {
+        *s ^= k;
+    }
+}
+
+fn sub_bytes(state: &mut [u8]) {
+    for s in state.iter_mut() {
+        *s = sub_byte(*s);
+    }
+}
+
+fn inv_sub_bytes(state: &mut [u8]) {
+    for s in state.iter_mut() {
+        *s = inv_sub_byte(*s);
+    }
+}
+
+fn shift_rows(state: &mut [u8]) {
+    let t: [u8; 16] = {
+        let mut t = [0u8; 16];
+        t.copy_from_slice(state);
+        t
+    };
+    for c in 0..4 {
+        for r in 0..4 {
+            state[4 * c + r] = t[4 * ((c + r) % 4) + r];
+        }
+    }
+}
+
+fn inv_shift_rows(state: &mut [u8]) {
+    let t: [u8; 16] = {
+        let mut t = [0u8; 16];
+        t.copy_from_slice(state);
+        t
+    };
+    for c in 0..4 {
+        for r in 0..4 {
+            state[4 * ((c + r) % 4) + r] = t[4 * c + r];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8]) {
+    for col in state.chunks_mut(4) {
+        let (a0, a1, a2, a3) = (col[0], col[1], col[2], col[3]);
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        col[0] = a0 ^ all ^ xtime(a0 ^ a1);
+        col[1] = a1 ^ all ^ xtime(a1 ^ a2);
+        col[2] = a2 ^ all ^ xtime(a2 ^ a3);
+        col[3] = a3 ^ all ^ xtime(a3 ^ a0);
+    }
+}
+
+fn inv_mix_columns(state: &mut [u8]) {
+    for col in state.chunks_mut(4) {
+        let (a0, a1, a2, a3) = (col[0], col[1], col[2], col[3]);
+        col[0] = gf_mul(a0, 14) ^ gf_mul(a1, 11) ^ gf_mul(a2, 13) ^ gf_mul(a3, 9);
+        col[1] = gf_mul(a0, 9) ^ gf_mul(a1, 14) ^ gf_mul(a2, 11) ^ gf_mul(a3, 13);
+        col[2] = gf_mul(a0, 13) ^ gf_mul(a1, 9) ^ gf_mul(a2, 14) ^ gf_mul(a3, 11);
+        col[3] = gf_mul(a0, 11) ^ gf_mul(a1, 13) ^ gf_mul(a2, 9) ^ gf_mul(a3, 14);
+    }
+}
+
+// FIPS 197 Section 5.2 KeyExpansion, round keys stored back to back
+fn expand_key(key: &[u8], round_keys: &mut [u8]) {
+    let nk = key.len() / 4;
+    round_keys[..key.len()].copy_from_slice(key);
+
+    let mut rcon = 1u8;
+    for i in nk..round_keys.len() / 4 {
+        let mut temp = [0u8; 4];
+        temp.copy_from_slice(&round_keys[(i - 1) * 4..i * 4]);
+        if i % nk == 0 {
+            temp.rotate_left(1);
+            for t in temp.iter_mut() {
+                *t = sub_byte(*t);
+            }
+            temp[0] ^= rcon;
+            rcon = xtime(rcon);
+        } else if nk > 6 && i % nk == 4 {
+            for t in temp.iter_mut() {
+                *t = sub_byte(*t);
+            }
+        }
+        for j in 0..4 {
+            round_keys[i * 4 + j] = round_keys[(i - nk) * 4 + j] ^ temp[j];
+        }
+    }
+}
+
+fn encrypt_block(round_keys: &[u8], rounds: usize, block: &mut [u8]) {
+    add_round_key(block, &round_keys[..BLOCK_LEN]);
+    for round in 1..rounds {
+        sub_bytes(block);
+        shift_rows(block);
+        mix_columns(block);
+        add_round_key(
+            block,
+            &round_keys[round * BLOCK_LEN..(round + 1) * BLOCK_LEN],
+        );
+    }
+    sub_bytes(block);
+    shift_rows(block);
+    add_round_key(block, &round_keys[rounds * BLOCK_LEN..]);
+}
+
+fn decrypt_block(round_keys: &[u8], rounds: usize, block: &mut [u8]) {
+    add_round_key(block, &round_keys[rounds * BLOCK_LEN..]);
+    for round in (1..rounds).rev() {
+        inv_shift_rows(block);
+        inv_sub_bytes(block);
+        add_round_key(
+            block,
+            &round_keys[round * BLOCK_LEN..(round + 1) * BLOCK_LEN],
+        );
+        inv_mix_columns(block);
+    }
+    inv_shift_rows(block);
+    inv_sub_bytes(block);
+    add_round_key(block, &round_keys[..BLOCK_LEN]);
+}
+
+macro_rules! impl_soft_aes_ecb {
+    ($name:tt, $key_len:expr, $rounds:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            round_keys: [u8; ($rounds + 1) * BLOCK_LEN],
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.round_keys.zeroize();
+            }
+        }
+
+        impl $name {
+            pub const KEY_LEN: usize = $key_len;
+            pub const BLOCK_LEN: usize = BLOCK_LEN;
+
+            pub fn new(key: &[u8]) -> Self {
+                assert_eq!(key.len(), Self::KEY_LEN);
+
+                let mut round_keys = [0u8; ($rounds + 1) * BLOCK_LEN];
+                expand_key(key, &mut round_keys);
+
+                Self { round_keys }
+            }
+
+            pub fn encrypt(&mut self, blocks: &mut [u8]) {
+                assert_eq!(blocks.len() % Self::BLOCK_LEN, 0);
+
+                for block in blocks.chunks_mut(Self::BLOCK_LEN) {
+                    encrypt_block(&self.round_keys, $rounds, block);
+                }
+            }
+
+            pub fn decrypt(&mut self, blocks: &mut [u8]) {
+                assert_eq!(blocks.len() % Self::BLOCK_LEN, 0);
+
+                for block in blocks.chunks_mut(Self::BLOCK_LEN) {
+                    decrypt_block(&self.round_keys, $rounds, block);
+                }
+            }
+        }
+    };
+}
+
+impl_soft_aes_ecb!(Aes128Ecb, 16, 10);
+impl_soft_aes_ecb!(Aes192Ecb, 24, 12);
+impl_soft_aes_ecb!(Aes256Ecb, 32, 14);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FIPS 197 Appendix C example vectors
+    const PLAIN: &str = "00112233445566778899aabbccddeeff";
+
+    #[test]
+    fn test_sbox() {
+        assert_eq!(sub_byte(0x00), 0x63);
+        assert_eq!(sub_byte(0x53), 0xed);
+        assert_eq!(sub_byte(0xff), 0x16);
+        for x in 0..=255u8 {
+            assert_eq!(inv_sub_byte(sub_byte(x)), x);
+        }
+    }
+
+    #[test]
+    fn test_aes128_fips197() {
+        let key = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let mut block = hex::decode(PLAIN).unwrap();
+        let mut cipher = Aes128Ecb::new(&key);
+        cipher.encrypt(&mut block);
+        assert_eq!(hex::encode(&block), "69c4e0d86a7b0430d8cdb78070b4c55a");
+        cipher.decrypt(&mut block);
+        assert_eq!(hex::encode(&block), PLAIN);
+    }
+
+    #[test]
+    fn test_aes192_fips197() {
+        let key = hex::decode("000102030405060708090a0b0c0d0e0f1011121314151617").unwrap();
+        let mut block = hex::decode(PLAIN).unwrap();
+        let mut cipher = Aes192Ecb::new(&key);
+        cipher.encrypt(&mut block);
+        assert_eq!(hex::encode(&block), "dda97ca4864cdfe06eaf70a0ec0d7191");
+        cipher.decrypt(&mut block);
+        assert_eq!(hex::encode(&block), PLAIN);
+    }
+
+    #[test]
+    fn test_aes256_fips197() {
+        let key = hex::decode("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+            .unwrap();
+        let mut block = hex::decode(PLAIN).unwrap();
+        let mut cipher = Aes256Ecb::new(&key);
+        cipher.encrypt(&mut block);
+        assert_eq!(hex::encode(&block), "8ea2b7ca516745bfeafc49904b496089");
+        cipher.decrypt(&mut block);
+        assert_eq!(hex::encode(&block), PLAIN);
+    }
+}
@@ -0,0 +1,31 @@
+use rand_core::{CryptoRng, RngCore};
+
+// Deterministic RNG for the tests of the randomized wraps (AES-GCM,
+// ChaCha20-Poly1305, RFC 3537 HMAC wrap, AKW1)
+
+/// Counts up from its seed, so nonces, pads and IVs are predictable
+pub(crate) struct CountingRng(pub(crate) u8);
+
+impl RngCore for CountingRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for b in dest {
+            *b = self.0;
+            self.0 = self.0.wrapping_add(1);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for CountingRng {}
@@ -0,0 +1,155 @@
+use crate::locked::locked_copy;
+use crate::{self_test, KeyWrapError, UnwrappedKey};
+use alloc::string::String;
+use alloc::vec::Vec;
+use des::cipher::generic_array::GenericArray;
+use des::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use des::TdesEde3;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+// TKW: Triple-DES key wrap (NIST SP 800-38F Section 6.4, `tkw` feature)
+//
+// KW with the block cipher swapped for three-key TDEA: 32-bit semiblocks,
+// a 32-bit step counter and ICV3 = A6A6A6A6. Only for reading and
+// re-wrapping keys held by legacy payment and HSM systems; new wraps
+// should use AES KW/KWP.
+
+const ICV3: [u8; 4] = [0xa6, 0xa6, 0xa6, 0xa6];
+// NIST SP 800-38F section 5.3.1: the plaintext is 2 to 2^28 - 1 semiblocks
+const TKW_MAX_SEMIBLOCKS: u64 = 1 << 28;
+
+fn tdes(kek: &[u8]) -> Result<TdesEde3, KeyWrapError> {
+    TdesEde3::new_from_slice(kek).map_err(|_| KeyWrapError::InvalidKekLength(kek.len()))
+}
+
+// TW over `buf` = A | R[1] | ... | R[n], in place
+fn tw(cipher: &TdesEde3, buf: &mut [u8]) {
+    let n = buf.len() / 4 - 1;
+    let mut b = GenericArray::from([0u8; 8]);
+    for j in 0..6 {
+        for i in 1..n + 1 {
+            b[..4].copy_from_slice(&buf[..4]);
+            b[4..].copy_from_slice(&buf[i * 4..i * 4 + 4]);
+            cipher.encrypt_block(&mut b);
+            let t = ((n * j + i) as u32).to_be_bytes();
+            for k in 0..4 {
+                buf[k] = b[k] ^ t[k];
+            }
+            buf[i * 4..i * 4 + 4].copy_from_slice(&b[4..]);
+        }
+    }
+    b.zeroize();
+}
+
+// TW^-1, the inverse of `tw`
+fn tw_inv(cipher: &TdesEde3, buf: &mut [u8]) {
+    let n = buf.len() / 4 - 1;
+    let mut b = GenericArray::from([0u8; 8]);
+    for j in (0..6).rev() {
+        for i in (1..n + 1).rev() {
+            let t = ((n * j + i) as u32).to_be_bytes();
+            for k in 0..4 {
+                b[k] = buf[k] ^ t[k];
+            }
+            b[4..].copy_from_slice(&buf[i * 4..i * 4 + 4]);
+            cipher.decrypt_block(&mut b);
+            buf[..4].copy_from_slice(&b[..4]);
+            buf[i * 4..i * 4 + 4].copy_from_slice(&b[4..]);
+        }
+    }
+    b.zeroize();
+}
+
+/// Wrap key with TDEA and ICV3 (SP 800-38F TKW); `kek` is a 24-byte
+/// three-key TDEA key and the plaintext a multiple of 4 bytes, at least 8
+pub fn try_tdes_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+    let cipher = tdes(kek)?;
+    let len = plaintext.len();
+    if len < 8 || !len.is_multiple_of(4) {
+        return Err(KeyWrapError::InvalidPlaintextLength(len));
+    }
+    if (len / 4) as u64 >= TKW_MAX_SEMIBLOCKS {
+        return Err(KeyWrapError::LengthOverflow(len));
+    }
+    self_test::power_up()?;
+
+    let mut buf: Vec<u8> = Vec::with_capacity(len + 4);
+    buf.extend_from_slice(&ICV3);
+    buf.extend_from_slice(plaintext);
+    tw(&cipher, &mut buf);
+    Ok(buf)
+}
+
+/// Unwrap key and check ICV3 (SP 800-38F TKW)
+pub fn try_tdes_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+    let cipher = tdes(kek)?;
+    let len = wrapped.len();
+    if len < 12 || !len.is_multiple_of(4) {
+        return Err(KeyWrapError::InvalidCiphertextLength(len));
+    }
+    if (len / 4) as u64 > TKW_MAX_SEMIBLOCKS {
+        return Err(KeyWrapError::LengthOverflow(len));
+    }
+    self_test::power_up()?;
+
+    let mut buf = locked_copy(wrapped)?;
+    tw_inv(&cipher, &mut buf);
+    if !bool::from(buf[..4].ct_eq(&ICV3[..])) {
+        return Err(KeyWrapError::IntegrityCheckFailed);
+    }
+    UnwrappedKey::from_slice(&buf[4..])
+}
+
+/// Wrap key with TDEA (SP 800-38F TKW)
+pub fn tdes_wrap_key(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_tdes_wrap_key(kek, plaintext)?)
+}
+
+/// Unwrap key with TDEA and check ICV3 (SP 800-38F TKW)
+pub fn tdes_unwrap_key(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(try_tdes_unwrap_key(kek, wrapped)?.expose_secret().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tkw() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F1011121314151617").unwrap();
+        for (plain, cipher) in [
+            ("0011223344556677", "16277D1DB80D82A76DE53A76"),
+            (
+                "00112233445566778899AABB",
+                "F49FC320848906FBCC3CFA03F47F621C",
+            ),
+            (
+                "00112233445566778899AABBCCDDEEFF",
+                "75F5F26521D739BA33F9619B52D2AB0D29822081",
+            ),
+        ]
+        .iter()
+        {
+            let plain = hex::decode(plain).unwrap();
+            let cipher = hex::decode(cipher).unwrap();
+            assert_eq!(cipher, tdes_wrap_key(&kek, &plain).unwrap());
+            assert_eq!(plain, tdes_unwrap_key(&kek, &cipher).unwrap());
+        }
+
+        let mut cipher = hex::decode("16277D1DB80D82A76DE53A76").unwrap();
+        cipher[0] ^= 1;
+        assert_eq!(
+            KeyWrapError::IntegrityCheckFailed,
+            try_tdes_unwrap_key(&kek, &cipher).unwrap_err()
+        );
+        assert_eq!(
+            KeyWrapError::InvalidPlaintextLength(6),
+            try_tdes_wrap_key(&kek, &[0u8; 6]).unwrap_err()
+        );
+        assert_eq!(
+            KeyWrapError::InvalidKekLength(16),
+            try_tdes_wrap_key(&kek[..16], &[0u8; 8]).unwrap_err()
+        );
+    }
+}
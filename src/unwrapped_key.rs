@@ -0,0 +1,34 @@
+use crate::locked::{locked_copy, LockedBuf};
+use crate::KeyWrapError;
+use core::fmt;
+
+/// Key material recovered by an unwrap, overwritten with zeros on drop
+///
+/// Returned by the `try_*` and opaque unwrap functions so callers don't
+/// have to remember to scrub the plaintext key themselves.
+pub struct UnwrappedKey(LockedBuf);
+
+impl UnwrappedKey {
+    // copied into locked memory with the `mlock` feature
+    pub(crate) fn from_slice(key: &[u8]) -> Result<UnwrappedKey, KeyWrapError> {
+        locked_copy(key).map(UnwrappedKey)
+    }
+
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for UnwrappedKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("UnwrappedKey(..redacted..)")
+    }
+}
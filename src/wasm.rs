@@ -0,0 +1,66 @@
+//! wasm-bindgen exports for JavaScript, `Uint8Array` in and out
+
+use crate::{
+    try_unwrap_key, try_unwrap_key_with_pad, try_wrap_key, try_wrap_key_with_pad, KeyWrapError,
+};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+// `Error` on the JS side, with the `KeyWrapError` message
+fn js_error(e: KeyWrapError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// Wrap key with the IV defined in RFC3394 (`wrap(kek, key)` in JS)
+#[wasm_bindgen]
+pub fn wrap(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, JsError> {
+    try_wrap_key(kek, key).map_err(js_error)
+}
+
+/// Unwrap key and Check IV in RFC3394 (`unwrap(kek, wrapped)` in JS)
+///
+/// The key is copied into a JS-owned `Uint8Array`; the Rust-side copy is
+/// zeroized before returning.
+#[wasm_bindgen]
+pub fn unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Uint8Array, JsError> {
+    try_unwrap_key(kek, wrapped)
+        .map(|key| Uint8Array::from(key.expose_secret()))
+        .map_err(js_error)
+}
+
+/// Wrap key with pad using padding algorithm (RFC5649) (`wrapWithPad` in JS)
+#[wasm_bindgen(js_name = wrapWithPad)]
+pub fn wrap_with_pad(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, JsError> {
+    try_wrap_key_with_pad(kek, key).map_err(js_error)
+}
+
+/// Unwrap key with pad using padding algorithm (RFC5649) (`unwrapWithPad` in JS)
+///
+/// The key is copied into a JS-owned `Uint8Array`; the Rust-side copy is
+/// zeroized before returning.
+#[wasm_bindgen(js_name = unwrapWithPad)]
+pub fn unwrap_with_pad(kek: &[u8], wrapped: &[u8]) -> Result<Uint8Array, JsError> {
+    try_unwrap_key_with_pad(kek, wrapped)
+        .map(|key| Uint8Array::from(key.expose_secret()))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // only wrap: JsError and the unwrap functions' Uint8Array need a JS host
+    #[test]
+    fn test_wasm_exports() {
+        let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let cipher = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+        let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        assert_eq!(cipher, wrap(&kek, &plain).unwrap());
+        assert_eq!(
+            try_wrap_key_with_pad(&kek, b"short").unwrap(),
+            wrap_with_pad(&kek, b"short").unwrap()
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{aes_wrap_key_and_iv, aes_wrap_key_with_pad, Algorithm, IV_3394};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Builder composing the algorithm choice and inputs of a single wrap
+///
+/// ```ignore
+/// let wrapped = WrapRequest::new()
+///     .algorithm(Algorithm::Kwp)
+///     .kek(&kek)
+///     .plaintext(&dek)
+///     .execute()?;
+/// ```
+#[derive(Clone)]
+pub struct WrapRequest<'a> {
+    algorithm: Algorithm,
+    kek: Option<&'a [u8]>,
+    iv: Option<&'a [u8]>,
+    aad: Option<&'a [u8]>,
+    plaintext: Option<&'a [u8]>,
+}
+
+impl<'a> Default for WrapRequest<'a> {
+    fn default() -> Self {
+        WrapRequest {
+            algorithm: Algorithm::Kw,
+            kek: None,
+            iv: None,
+            aad: None,
+            plaintext: None,
+        }
+    }
+}
+
+// kek and plaintext are shown by length only
+impl<'a> fmt::Debug for WrapRequest<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WrapRequest")
+            .field("algorithm", &self.algorithm)
+            .field(
+                "kek",
+                &self
+                    .kek
+                    .map(|k| format!("Kek{}(..redacted..)", k.len() * 8)),
+            )
+            .field("iv", &self.iv)
+            .field("aad", &self.aad)
+            .field(
+                "plaintext",
+                &self
+                    .plaintext
+                    .map(|p| format!("{} bytes, ..redacted..", p.len())),
+            )
+            .finish()
+    }
+}
+
+impl<'a> WrapRequest<'a> {
+    /// New request using RFC3394 (`Algorithm::Kw`) unless told otherwise
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select the wrapping algorithm
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Key-encryption key (16, 24 or 32 bytes)
+    pub fn kek(mut self, kek: &'a [u8]) -> Self {
+        self.kek = Some(kek);
+        self
+    }
+
+    /// 8-octet initial value for `Algorithm::Kw` (defaults to `IV_3394`)
+    pub fn iv(mut self, iv: &'a [u8]) -> Self {
+        self.iv = Some(iv);
+        self
+    }
+
+    /// Additional authenticated data for the AEAD algorithms (none by
+    /// default); the same bytes must be given to unwrap
+    pub fn aad(mut self, aad: &'a [u8]) -> Self {
+        self.aad = Some(aad);
+        self
+    }
+
+    /// Key material to wrap
+    pub fn plaintext(mut self, plaintext: &'a [u8]) -> Self {
+        self.plaintext = Some(plaintext);
+        self
+    }
+
+    /// Wrap the plaintext with the configured algorithm
+    pub fn execute(&self) -> Result<Vec<u8>, String> {
+        let kek = self.kek.ok_or_else(|| String::from("kek is not set"))?;
+        let plaintext = self
+            .plaintext
+            .ok_or_else(|| String::from("plaintext is not set"))?;
+
+        if let Some(iv) = self.iv {
+            if iv.len() != 8 {
+                return Err(format!("iv must be 8 octets, got {}", iv.len()));
+            }
+        }
+
+        match self.algorithm {
+            Algorithm::Kw | Algorithm::Kwp if self.aad.is_some() => {
+                Err(format!("aad is not supported with {:?}", self.algorithm))
+            }
+            Algorithm::Kw => aes_wrap_key_and_iv(kek, plaintext, self.iv.unwrap_or(&IV_3394)),
+            Algorithm::Kwp => {
+                if self.iv.is_some() {
+                    return Err(String::from("iv is not supported with Kwp"));
+                }
+                aes_wrap_key_with_pad(kek, plaintext)
+            }
+            #[cfg(feature = "gcm-siv")]
+            Algorithm::AesGcmSiv => {
+                if self.iv.is_some() {
+                    return Err(String::from("iv is not supported with AesGcmSiv"));
+                }
+                Ok(crate::try_aes_gcm_siv_wrap_key(
+                    kek,
+                    plaintext,
+                    self.aad.unwrap_or(&[]),
+                )?)
+            }
+            #[cfg(feature = "gcm")]
+            Algorithm::AesGcm => {
+                if self.iv.is_some() {
+                    return Err(String::from("iv is not supported with AesGcm"));
+                }
+                Ok(crate::try_aes_gcm_wrap_key(
+                    kek,
+                    plaintext,
+                    self.aad.unwrap_or(&[]),
+                )?)
+            }
+            #[cfg(feature = "chacha")]
+            Algorithm::ChaCha20Poly1305 => {
+                if self.iv.is_some() {
+                    return Err(String::from("iv is not supported with ChaCha20Poly1305"));
+                }
+                Ok(crate::try_chacha20poly1305_wrap_key(
+                    kek,
+                    plaintext,
+                    self.aad.unwrap_or(&[]),
+                )?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aes_wrap_key, aes_wrap_key_with_pad};
+
+    #[test]
+    fn test_wrap_request_matches_free_functions() {
+        let kek = hex::decode("5840DF6E29B02AF1AB493B705BF16EA1AE8338F4DCC176A8").unwrap();
+        let plain = hex::decode("C37B7E6492584340BED12207808941155068F738").unwrap();
+        let wrapped = WrapRequest::new()
+            .algorithm(Algorithm::Kwp)
+            .kek(&kek)
+            .plaintext(&plain)
+            .execute()
+            .unwrap();
+        assert_eq!(aes_wrap_key_with_pad(&kek, &plain).unwrap(), wrapped);
+
+        let wrapped = WrapRequest::new()
+            .kek(&kek)
+            .plaintext(&plain[..16])
+            .execute()
+            .unwrap();
+        assert_eq!(aes_wrap_key(&kek, &plain[..16]).unwrap(), wrapped);
+    }
+
+    #[test]
+    fn test_wrap_request_debug_is_redacted() {
+        let request = WrapRequest::new()
+            .kek(&[0x42u8; 16])
+            .plaintext(&[0x42u8; 24]);
+        let debug = format!("{:?}", request);
+        assert!(debug.contains("Kek128(..redacted..)"), "{}", debug);
+        assert!(debug.contains("24 bytes"), "{}", debug);
+        assert!(!debug.contains("66"), "{}", debug);
+    }
+
+    #[test]
+    fn test_wrap_request_missing_inputs() {
+        let kek = [0u8; 16];
+        assert!(WrapRequest::new().plaintext(&kek).execute().is_err());
+        assert!(WrapRequest::new().kek(&kek).execute().is_err());
+        assert!(WrapRequest::new()
+            .algorithm(Algorithm::Kwp)
+            .kek(&kek)
+            .iv(&IV_3394)
+            .plaintext(&kek)
+            .execute()
+            .is_err());
+        for iv in [&[1u8, 2, 3][..], &[0xa6; 9][..]].iter() {
+            assert_eq!(
+                Err(format!("iv must be 8 octets, got {}", iv.len())),
+                WrapRequest::new()
+                    .kek(&kek)
+                    .iv(iv)
+                    .plaintext(&kek)
+                    .execute()
+            );
+        }
+        assert!(WrapRequest::new()
+            .kek(&kek)
+            .aad(b"ctx")
+            .plaintext(&kek)
+            .execute()
+            .is_err());
+    }
+
+    #[cfg(feature = "gcm-siv")]
+    #[test]
+    fn test_wrap_request_aad() {
+        let kek = [0x42u8; 16];
+        let wrapped = WrapRequest::new()
+            .algorithm(Algorithm::AesGcmSiv)
+            .kek(&kek)
+            .aad(b"ctx")
+            .plaintext(&[7u8; 16])
+            .execute()
+            .unwrap();
+        assert_eq!(
+            crate::try_aes_gcm_siv_wrap_key(&kek, &[7u8; 16], b"ctx").unwrap(),
+            wrapped
+        );
+    }
+}
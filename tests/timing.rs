@@ -0,0 +1,124 @@
+// dudect-style timing-leak checks for the unwrap failure path
+// * "Dude, is my code constant time?" (Reparaz, Balasch, Verbauwhede, 2017)
+//   https://eprint.iacr.org/2016/1123.pdf
+//
+// Two input classes are measured in random interleaving, the slowest tail
+// is cropped, and Welch's t-test is applied; |t| above 4.5 is the usual
+// dudect threshold for "timing depends on the class". Run these on real
+// hardware with optimizations:
+//
+//     cargo test --release --features timing-tests --test timing
+#![cfg(feature = "timing-tests")]
+
+use aes_keywrap_rs::{aes_unwrap_key, aes_verify_wrapped, aes_wrap_key_and_iv, IV_3394};
+use std::hint::black_box;
+use std::time::Instant;
+
+const SAMPLES: usize = 100_000;
+const T_THRESHOLD: f64 = 4.5;
+const CROP_PERCENTILE: f64 = 0.9;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next_bit(&mut self) -> bool {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 & 1 == 1
+    }
+}
+
+fn measure<F: FnMut(bool)>(mut f: F) -> (Vec<f64>, Vec<f64>) {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    let mut samples: Vec<(bool, f64)> = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        let class = rng.next_bit();
+        let start = Instant::now();
+        f(class);
+        samples.push((class, start.elapsed().as_nanos() as f64));
+    }
+
+    let mut sorted: Vec<f64> = samples.iter().map(|s| s.1).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let cutoff = sorted[(sorted.len() as f64 * CROP_PERCENTILE) as usize];
+
+    let kept = samples.iter().filter(|s| s.1 <= cutoff);
+    let a = kept.clone().filter(|s| !s.0).map(|s| s.1).collect();
+    let b = kept.filter(|s| s.0).map(|s| s.1).collect();
+    (a, b)
+}
+
+fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    let var =
+        |v: &[f64], m: f64| v.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / (v.len() - 1) as f64;
+    let (ma, mb) = (mean(a), mean(b));
+    let (va, vb) = (var(a, ma), var(b, mb));
+    (ma - mb) / (va / a.len() as f64 + vb / b.len() as f64).sqrt()
+}
+
+fn assert_no_leak(name: &str, a: &[f64], b: &[f64]) {
+    let t = welch_t(a, b);
+    assert!(
+        t.abs() < T_THRESHOLD,
+        "{}: timing differs between classes (t = {:.2}, n = {}/{})",
+        name,
+        t,
+        a.len(),
+        b.len()
+    );
+}
+
+// A valid blob against the same blob with one ciphertext bit flipped.
+// Whether the unwrap succeeded is visible to the caller anyway; what must
+// not depend on it is the time W^-1 and the ICV comparison take. The
+// verify-only unwrap is measured, as it does the same work either way:
+// both classes unwrap into a locked buffer that is wiped on return, and
+// neither copies a key out.
+#[test]
+fn test_unwrap_valid_vs_tampered() {
+    let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+    let valid = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+    let mut tampered = valid.clone();
+    tampered[12] ^= 0x10;
+
+    let (a, b) = measure(|class| {
+        let wrapped = if class { &tampered } else { &valid };
+        let _ = black_box(aes_verify_wrapped(black_box(&kek), black_box(wrapped)));
+    });
+    assert_no_leak("valid vs tampered", &a, &b);
+}
+
+// Both classes fail the integrity check, so what is compared is *how* the
+// unwrap failed.
+#[test]
+fn test_unwrap_early_vs_late_icv_mismatch() {
+    let kek = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+    let plain = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+    let mut early_iv = IV_3394;
+    early_iv[0] ^= 1;
+    let mut late_iv = IV_3394;
+    late_iv[7] ^= 1;
+    let early = aes_wrap_key_and_iv(&kek, &plain, &early_iv).unwrap();
+    let late = aes_wrap_key_and_iv(&kek, &plain, &late_iv).unwrap();
+
+    let (a, b) = measure(|class| {
+        let wrapped = if class { &late } else { &early };
+        let _ = black_box(aes_unwrap_key(black_box(&kek), black_box(wrapped)));
+    });
+    assert_no_leak("early vs late ICV mismatch", &a, &b);
+}
+
+#[test]
+fn test_unwrap_differing_wrong_keks() {
+    let kek_a = hex::decode("F0E1D2C3B4A5968778695A4B3C2D1E0F").unwrap();
+    let kek_b = hex::decode("0F1E2D3C4B5A69788796A5B4C3D2E1F0").unwrap();
+    let wrapped = hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap();
+
+    let (a, b) = measure(|class| {
+        let kek = if class { &kek_b } else { &kek_a };
+        let _ = black_box(aes_unwrap_key(black_box(kek), black_box(&wrapped)));
+    });
+    assert_no_leak("wrong kek a vs wrong kek b", &a, &b);
+}